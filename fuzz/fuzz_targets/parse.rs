@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::io::Cursor;
+
+// Any input may fail to parse, but none may panic.
+fuzz_target!(|data: &[u8]| {
+	// Header, description, colorset and brick lines.
+	if let Ok(reader) = bl_save::Reader::from_bytes(data) {
+		for _brick in reader {}
+	}
+
+	// Parse errors are skipped instead of yielded, which takes a different
+	// path when assembling bricks and their extra data.
+	if let Ok(reader) = bl_save::ReaderBuilder::new(Cursor::new(data))
+		.error_recovery(true)
+		.build()
+	{
+		for _brick in reader {}
+	}
+
+	let _ = bl_save::read_description_only(Cursor::new(data));
+	let _ = bl_save::read_brick_count_only(Cursor::new(data));
+});
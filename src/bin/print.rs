@@ -18,8 +18,10 @@ fn main() -> io::Result<()> {
 
 	let mut read_bricks = 0;
 
+	println!("Bricks:");
+
 	for brick in reader {
-		let _brick = brick?;
+		println!("{}", brick?);
 		read_bricks += 1;
 	}
 
@@ -0,0 +1,170 @@
+//! Check a save file for common problems.
+//!
+//! Usage: `validate [--strict] [--json] <path>`
+//!
+//! Prints one issue per line, prefixed with `ERROR`, `WARN` or `INFO`.
+//! Exits with 0 if the file is clean, 1 if there are warnings and 2 if
+//! there are errors. With `--strict`, warnings count as errors.
+
+use bl_save::{query, validate_bls_file, Reader, Warning};
+use std::{
+	env,
+	fs::File,
+	io::{self, BufReader},
+	path::Path,
+	process,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Severity {
+	Error,
+	Warn,
+	Info,
+}
+
+impl Severity {
+	fn label(self) -> &'static str {
+		match self {
+			Severity::Error => "ERROR",
+			Severity::Warn => "WARN",
+			Severity::Info => "INFO",
+		}
+	}
+}
+
+fn main() {
+	let mut strict = false;
+	let mut json = false;
+	let mut path = None;
+
+	for arg in env::args().skip(1) {
+		match arg.as_str() {
+			"--strict" => strict = true,
+			"--json" => json = true,
+			_ if path.is_none() => path = Some(arg),
+			_ => usage(),
+		}
+	}
+
+	let path = path.unwrap_or_else(|| usage());
+	let mut issues = check(Path::new(&path));
+
+	if strict {
+		for issue in &mut issues {
+			if issue.0 == Severity::Warn {
+				issue.0 = Severity::Error;
+			}
+		}
+	}
+
+	let errors = count(&issues, Severity::Error);
+	let warnings = count(&issues, Severity::Warn);
+
+	if json {
+		print_json(&path, &issues, errors, warnings);
+	} else {
+		for (severity, message) in &issues {
+			println!("{} {}", severity.label(), message);
+		}
+		println!("INFO {} errors, {} warnings", errors, warnings);
+	}
+
+	process::exit(if errors > 0 {
+		2
+	} else if warnings > 0 {
+		1
+	} else {
+		0
+	});
+}
+
+fn usage() -> ! {
+	eprintln!("usage: validate [--strict] [--json] <path>");
+	process::exit(2);
+}
+
+fn check(path: &Path) -> Vec<(Severity, String)> {
+	let mut issues = Vec::new();
+
+	// Header, linecount and brick fields.
+	match validate_bls_file(path) {
+		Ok(summary) => {
+			issues.push((Severity::Info, format!("{} bricks read", summary.brick_count)));
+			for warning in summary.warnings {
+				issues.push((severity(&warning), warning.to_string()));
+			}
+		}
+		Err(e) => {
+			issues.push((Severity::Error, e.to_string()));
+			return issues;
+		}
+	}
+
+	// Bricks of the same type at the same spot.
+	match read_bricks(path) {
+		Ok(bricks) => {
+			for (first, duplicate) in query::find_overlapping(&bricks) {
+				issues.push((
+					Severity::Warn,
+					format!("brick {}: overlaps brick {}", duplicate, first),
+				));
+			}
+		}
+		Err(e) => issues.push((Severity::Error, e.to_string())),
+	}
+
+	issues
+}
+
+fn severity(warning: &Warning) -> Severity {
+	match warning {
+		Warning::InvalidHeader(_) | Warning::NonFinitePosition { .. } => Severity::Error,
+		_ => Severity::Warn,
+	}
+}
+
+fn read_bricks(path: &Path) -> io::Result<Vec<bl_save::Brick>> {
+	Reader::new(BufReader::new(File::open(path)?))?.collect()
+}
+
+fn count(issues: &[(Severity, String)], severity: Severity) -> usize {
+	issues.iter().filter(|issue| issue.0 == severity).count()
+}
+
+fn print_json(path: &str, issues: &[(Severity, String)], errors: usize, warnings: usize) {
+	let issues: Vec<String> = issues
+		.iter()
+		.map(|(severity, message)| {
+			format!(
+				"{{\"severity\":\"{}\",\"message\":{}}}",
+				severity.label(),
+				json_string(message)
+			)
+		})
+		.collect();
+
+	println!(
+		"{{\"path\":{},\"errors\":{},\"warnings\":{},\"issues\":[{}]}}",
+		json_string(path),
+		errors,
+		warnings,
+		issues.join(",")
+	);
+}
+
+fn json_string(s: &str) -> String {
+	let mut escaped = String::from("\"");
+	for c in s.chars() {
+		match c {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			c => escaped.push(c),
+		}
+	}
+	escaped.push('"');
+	escaped
+}
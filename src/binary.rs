@@ -0,0 +1,182 @@
+use crate::{cp1252::BYTE_TO_CHAR, BrickBase};
+use std::io;
+
+const NAME_OFFSET: usize = 15;
+
+impl<S: AsRef<str>> BrickBase<S> {
+	/// Pack the brick into a fixed-size 32-byte record.
+	///
+	/// | Bytes   | Content                                          |
+	/// |---------|--------------------------------------------------|
+	/// | 0–11    | `position` as three big-endian `f32`s            |
+	/// | 12–14   | Packed fields, most significant bit first        |
+	/// | 15–31   | `ui_name` in Windows-1252, padded with zeros     |
+	///
+	/// The packed fields are `angle` (2 bits), `is_baseplate` (1),
+	/// `color_index` (6), `color_fx` (4), `shape_fx` (4), `raycasting` (1),
+	/// `collision` (1) and `rendering` (1), followed by 4 zero bits.
+	///
+	/// The record is lossy: `print` is not stored, `ui_name` is truncated to
+	/// 17 bytes, characters outside Windows-1252 become `?` and values too
+	/// large for their field keep only their low bits.
+	pub fn to_binary_record(&self) -> [u8; 32] {
+		let mut record = [0; 32];
+
+		record[0..4].copy_from_slice(&self.position.0.to_be_bytes());
+		record[4..8].copy_from_slice(&self.position.1.to_be_bytes());
+		record[8..12].copy_from_slice(&self.position.2.to_be_bytes());
+
+		let packed = u32::from(self.angle & 0b11) << 22
+			| u32::from(self.is_baseplate) << 21
+			| u32::from(self.color_index & 0b11_1111) << 15
+			| u32::from(self.color_fx & 0b1111) << 11
+			| u32::from(self.shape_fx & 0b1111) << 7
+			| u32::from(self.raycasting) << 6
+			| u32::from(self.collision) << 5
+			| u32::from(self.rendering) << 4;
+		record[12..15].copy_from_slice(&packed.to_be_bytes()[1..]);
+
+		let name = self.ui_name.as_ref().chars().map(encode_char);
+		for (dst, byte) in record[NAME_OFFSET..].iter_mut().zip(name) {
+			*dst = byte;
+		}
+
+		record
+	}
+}
+
+impl BrickBase {
+	/// Read a record written by
+	/// [`to_binary_record`](#method.to_binary_record). `print` is set to "".
+	///
+	/// Fails if the unused bits of the record are set.
+	pub fn from_binary_record(bytes: &[u8; 32]) -> io::Result<Self> {
+		let float = |offset: usize| {
+			let mut buf = [0; 4];
+			buf.copy_from_slice(&bytes[offset..offset + 4]);
+			f32::from_be_bytes(buf)
+		};
+
+		let packed = u32::from_be_bytes([0, bytes[12], bytes[13], bytes[14]]);
+		if packed & 0b1111 != 0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"Unused bits are set in binary brick record",
+			));
+		}
+		let field = |shift: u32, mask: u32| ((packed >> shift) & mask) as u8;
+
+		let ui_name = bytes[NAME_OFFSET..]
+			.iter()
+			.take_while(|&&byte| byte != 0)
+			.map(|&byte| BYTE_TO_CHAR[usize::from(byte)])
+			.collect();
+
+		Ok(Self {
+			ui_name,
+			position: (float(0), float(4), float(8)),
+			angle: field(22, 0b11),
+			is_baseplate: field(21, 1) != 0,
+			color_index: field(15, 0b11_1111),
+			print: String::new(),
+			color_fx: field(11, 0b1111),
+			shape_fx: field(7, 0b1111),
+			raycasting: field(6, 1) != 0,
+			collision: field(5, 1) != 0,
+			rendering: field(4, 1) != 0,
+		})
+	}
+}
+
+fn encode_char(c: char) -> u8 {
+	BYTE_TO_CHAR
+		.iter()
+		.position(|&other| other == c)
+		.map_or(b'?', |byte| byte as u8)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trips_fields_at_their_maximum() {
+		let brick = BrickBase {
+			ui_name: String::from("2x2 Plate"),
+			position: (-1.5, f32::MAX, 0.2),
+			angle: 3,
+			is_baseplate: true,
+			color_index: 63,
+			print: String::new(),
+			color_fx: 15,
+			shape_fx: 15,
+			raycasting: true,
+			collision: true,
+			rendering: true,
+		};
+		let record = brick.to_binary_record();
+		assert_eq!(&record[12..15], &[0xff, 0xff, 0xf0]);
+		assert_eq!(BrickBase::from_binary_record(&record).unwrap(), brick);
+
+		let cleared = BrickBase {
+			ui_name: String::new(),
+			position: (0.0, 0.0, 0.0),
+			angle: 0,
+			is_baseplate: false,
+			color_index: 0,
+			print: String::new(),
+			color_fx: 0,
+			shape_fx: 0,
+			raycasting: false,
+			collision: false,
+			rendering: false,
+		};
+		let record = cleared.to_binary_record();
+		assert_eq!(record, [0; 32]);
+		assert_eq!(BrickBase::from_binary_record(&record).unwrap(), cleared);
+	}
+
+	#[test]
+	fn out_of_range_fields_keep_their_low_bits() {
+		let brick = BrickBase {
+			angle: 5,
+			color_index: 64 + 9,
+			color_fx: 16 + 3,
+			..BrickBase::with_defaults()
+		};
+		let read = BrickBase::from_binary_record(&brick.to_binary_record()).unwrap();
+		assert_eq!((read.angle, read.color_index, read.color_fx), (1, 9, 3));
+	}
+
+	#[test]
+	fn lossy_fields() {
+		let brick = BrickBase {
+			ui_name: String::from("Castle Wall Window Arch"),
+			print: String::from("Letters/A"),
+			..BrickBase::with_defaults()
+		};
+		let read = BrickBase::from_binary_record(&brick.to_binary_record()).unwrap();
+		assert_eq!(read.ui_name, "Castle Wall Windo");
+		assert_eq!(read.print, "");
+
+		let brick = BrickBase {
+			ui_name: String::from("\u{20ac}5 Caf\u{e9} \u{2603}"),
+			..BrickBase::with_defaults()
+		};
+		let record = brick.to_binary_record();
+		assert_eq!(&record[NAME_OFFSET..NAME_OFFSET + 9], b"\x805 Caf\xe9 ?");
+		let read = BrickBase::from_binary_record(&record).unwrap();
+		assert_eq!(read.ui_name, "\u{20ac}5 Caf\u{e9} ?");
+	}
+
+	#[test]
+	fn rejects_unused_bits() {
+		let record = BrickBase::with_defaults().to_binary_record();
+		for bit in 0..4 {
+			let mut record = record;
+			record[14] |= 1 << bit;
+			let error = BrickBase::from_binary_record(&record).unwrap_err();
+			assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		}
+	}
+}
@@ -0,0 +1,374 @@
+//! Utilities for inspecting colorsets.
+
+use crate::{read::parse_color, Color, Colors};
+use std::fmt::Write as _;
+use std::io::{self, prelude::*};
+
+/// Describe a colorset as a table with one row per color index,
+/// showing the hex value (`#RRGGBBAA`) and whether the color is opaque.
+///
+/// With the `ansi` feature enabled, each row also includes a swatch of the
+/// color using ANSI terminal escapes.
+pub fn describe_colorset(colors: &Colors) -> String {
+	let mut table = String::from("Index  Hex        Alpha\n");
+
+	for (index, color) in colors.iter().enumerate() {
+		let alpha = if color.3 >= 1.0 {
+			"opaque"
+		} else if color.3 <= 0.0 {
+			"invisible"
+		} else {
+			"transparent"
+		};
+
+		write!(table, "{:>5}  {}  {:<11}", index, hex(*color), alpha).unwrap();
+
+		#[cfg(feature = "ansi")]
+		write!(
+			table,
+			"  \x1b[48;2;{};{};{}m    \x1b[0m",
+			channel(color.0),
+			channel(color.1),
+			channel(color.2),
+		)
+		.unwrap();
+
+		table.push('\n');
+	}
+
+	table
+}
+
+/// A bitmask of the color slots with a non-zero alpha, where bit `i` is set
+/// for color index `i`.
+pub fn alpha_mask(colors: &Colors) -> u64 {
+	mask(colors, |color| color.3 > 0.0)
+}
+
+/// A bitmask of the fully opaque color slots, where bit `i` is set for
+/// color index `i`.
+pub fn opaque_mask(colors: &Colors) -> u64 {
+	mask(colors, |color| color.3 >= 1.0)
+}
+
+/// The number of fully opaque color slots.
+pub fn opaque_count(colors: &Colors) -> u32 {
+	opaque_mask(colors).count_ones()
+}
+
+/// The number of color slots that are visible but not fully opaque.
+pub fn transparent_count(colors: &Colors) -> u32 {
+	(alpha_mask(colors) & !opaque_mask(colors)).count_ones()
+}
+
+/// Compare two colorsets, returning the index, old color and new color of
+/// each slot that differs.
+pub fn colorset_diff(a: &Colors, b: &Colors) -> Vec<(usize, Color, Color)> {
+	a.iter()
+		.zip(b.iter())
+		.enumerate()
+		.filter(|(_, (a, b))| a != b)
+		.map(|(index, (a, b))| (index, *a, *b))
+		.collect()
+}
+
+/// Read a standalone colorset: 64 lines of `R G B A`, in the format of the
+/// colorset block in save files. Trailing blank lines are ignored.
+pub fn read_palette(r: impl BufRead) -> io::Result<Colors> {
+	let mut colors = [Default::default(); 64];
+	let mut lines = r.lines();
+
+	for color in colors.iter_mut() {
+		match lines.next() {
+			Some(line) => *color = parse_color(&line?),
+			None => return Err(invalid_palette("Palette has fewer than 64 colors")),
+		}
+	}
+
+	for line in lines {
+		if !line?.trim().is_empty() {
+			return Err(invalid_palette("Palette has more than 64 colors"));
+		}
+	}
+
+	Ok(colors)
+}
+
+/// Write a colorset in the format read by
+/// [`read_palette`](fn.read_palette.html), with six decimal places per
+/// channel like Blockland.
+pub fn write_palette(w: &mut impl io::Write, colors: &Colors) -> io::Result<()> {
+	for color in colors.iter() {
+		write!(
+			w,
+			"{:.6} {:.6} {:.6} {:.6}\r\n",
+			color.0, color.1, color.2, color.3
+		)?;
+	}
+	Ok(())
+}
+
+/// The WCAG contrast ratio between two colors, from 1 (no contrast) to 21
+/// (black on white). Alpha is ignored.
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+	let (a, b) = (relative_luminance(a), relative_luminance(b));
+	let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+	(lighter + 0.05) / (darker + 0.05)
+}
+
+/// Compute the [`contrast_ratio`](fn.contrast_ratio.html) between every
+/// pair of colors in a colorset, indexed by color index.
+pub fn contrast_matrix(colors: &Colors) -> [[f32; 64]; 64] {
+	let mut matrix = [[1.0; 64]; 64];
+	for (i, a) in colors.iter().enumerate() {
+		for (j, b) in colors.iter().enumerate().skip(i + 1) {
+			let ratio = contrast_ratio(*a, *b);
+			matrix[i][j] = ratio;
+			matrix[j][i] = ratio;
+		}
+	}
+	matrix
+}
+
+/// Convert a color to HSL, ignoring alpha.
+///
+/// Returns the hue in degrees from `0` up to `360`, and the saturation and
+/// lightness from `0` through `1`. Grays have a hue and saturation of `0`.
+pub fn to_hsl(color: Color) -> (f32, f32, f32) {
+	let (r, g, b) = (
+		color.0.clamp(0.0, 1.0),
+		color.1.clamp(0.0, 1.0),
+		color.2.clamp(0.0, 1.0),
+	);
+	let max = r.max(g).max(b);
+	let min = r.min(g).min(b);
+	let lightness = (max + min) / 2.0;
+	let chroma = max - min;
+
+	if chroma == 0.0 {
+		return (0.0, 0.0, lightness);
+	}
+
+	let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+	let sector = if max == r {
+		((g - b) / chroma).rem_euclid(6.0)
+	} else if max == g {
+		(b - r) / chroma + 2.0
+	} else {
+		(r - g) / chroma + 4.0
+	};
+
+	((sector * 60.0) % 360.0, saturation, lightness)
+}
+
+/// The colors of a colorset with their indices, sorted by hue, then
+/// saturation, then lightness. See [`to_hsl`](fn.to_hsl.html).
+pub fn sort_by_hue(colors: &Colors) -> [(u8, Color); 64] {
+	let mut sorted = indexed(colors);
+	sorted.sort_by(|a, b| {
+		let (a, b) = (to_hsl(a.1), to_hsl(b.1));
+		a.0.total_cmp(&b.0)
+			.then(a.1.total_cmp(&b.1))
+			.then(a.2.total_cmp(&b.2))
+	});
+	sorted
+}
+
+/// The colors of a colorset with their indices, sorted from darkest to
+/// lightest by relative luminance.
+pub fn sort_by_luminance(colors: &Colors) -> [(u8, Color); 64] {
+	let mut sorted = indexed(colors);
+	sorted.sort_by(|a, b| relative_luminance(a.1).total_cmp(&relative_luminance(b.1)));
+	sorted
+}
+
+fn indexed(colors: &Colors) -> [(u8, Color); 64] {
+	let mut indexed = [(0, Color::default()); 64];
+	for (index, (slot, color)) in indexed.iter_mut().zip(colors.iter()).enumerate() {
+		*slot = (index as u8, *color);
+	}
+	indexed
+}
+
+fn invalid_palette(error: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn relative_luminance(color: Color) -> f32 {
+	fn linear(value: f32) -> f32 {
+		let value = value.clamp(0.0, 1.0);
+		if value <= 0.03928 {
+			value / 12.92
+		} else {
+			((value + 0.055) / 1.055).powf(2.4)
+		}
+	}
+
+	0.2126 * linear(color.0) + 0.7152 * linear(color.1) + 0.0722 * linear(color.2)
+}
+
+fn mask(colors: &Colors, include: impl Fn(&Color) -> bool) -> u64 {
+	colors
+		.iter()
+		.enumerate()
+		.filter(|(_, color)| include(color))
+		.fold(0, |mask, (index, _)| mask | 1 << index)
+}
+
+fn hex(color: Color) -> String {
+	format!(
+		"#{:02X}{:02X}{:02X}{:02X}",
+		channel(color.0),
+		channel(color.1),
+		channel(color.2),
+		channel(color.3),
+	)
+}
+
+fn channel(value: f32) -> u8 {
+	(value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	#[test]
+	fn palette_round_trip_is_lossless() {
+		let palette = fixture::palette();
+		let colors = read_palette(palette.as_bytes()).unwrap();
+		assert_eq!(colors[0], (0.898039, 0.0, 0.0, 1.0));
+		assert_eq!(colors[63], (0.5, 0.5, 0.5, 1.0));
+
+		let mut written = Vec::new();
+		write_palette(&mut written, &colors).unwrap();
+		assert_eq!(String::from_utf8(written).unwrap(), palette);
+		assert_eq!(read_palette(palette.as_bytes()).unwrap(), colors);
+	}
+
+	#[test]
+	fn palette_must_have_64_colors() {
+		let palette = fixture::palette();
+		let short: String = palette.lines().skip(1).map(|line| format!("{}\n", line)).collect();
+		assert_eq!(
+			read_palette(short.as_bytes()).unwrap_err().to_string(),
+			"Palette has fewer than 64 colors"
+		);
+
+		let long = format!("{}1 1 1 1\r\n", palette);
+		assert_eq!(
+			read_palette(long.as_bytes()).unwrap_err().to_string(),
+			"Palette has more than 64 colors"
+		);
+		assert!(read_palette(format!("{}\r\n\r\n", palette).as_bytes()).is_ok());
+	}
+
+	#[test]
+	fn sorting_permutes_the_colorset() {
+		let colors = read_palette(fixture::palette().as_bytes()).unwrap();
+
+		for sorted in &[sort_by_hue(&colors), sort_by_luminance(&colors)] {
+			let mut indices: Vec<u8> = sorted.iter().map(|&(index, _)| index).collect();
+			indices.sort_unstable();
+			assert_eq!(indices, (0..64).collect::<Vec<u8>>());
+			for &(index, color) in sorted.iter() {
+				assert_eq!(color, colors[usize::from(index)]);
+			}
+		}
+
+		let hue = sort_by_hue(&colors);
+		let position = |index: u8| hue.iter().position(|&(other, _)| other == index).unwrap();
+		// Red, then yellow, green and blue.
+		assert!(position(0) < position(1));
+		assert!(position(1) < position(2));
+		assert!(position(2) < position(3));
+
+		let luminance = sort_by_luminance(&colors);
+		assert_eq!(luminance[0].0, 5);
+		// White and the invisible white, kept in their original order.
+		assert_eq!((luminance[62].0, luminance[63].0), (4, 7));
+	}
+
+	#[test]
+	fn hsl_of_primary_colors() {
+		assert_eq!(to_hsl((1.0, 0.0, 0.0, 1.0)), (0.0, 1.0, 0.5));
+		assert_eq!(to_hsl((0.0, 1.0, 0.0, 0.0)), (120.0, 1.0, 0.5));
+		assert_eq!(to_hsl((0.0, 0.0, 1.0, 1.0)), (240.0, 1.0, 0.5));
+		assert_eq!(to_hsl((0.5, 0.5, 0.5, 1.0)), (0.0, 0.0, 0.5));
+	}
+
+	#[test]
+	fn masks_and_counts() {
+		let colors = read_palette(fixture::palette().as_bytes()).unwrap();
+		// Slot 6 is translucent and slot 7 invisible.
+		assert_eq!(alpha_mask(&colors), !(1 << 7));
+		assert_eq!(opaque_mask(&colors), !(1 << 6 | 1 << 7));
+		assert_eq!(opaque_count(&colors), 62);
+		assert_eq!(transparent_count(&colors), 1);
+
+		let mut colors = colors;
+		colors[63].3 = 1.5;
+		colors[62].3 = -1.0;
+		assert_eq!(opaque_mask(&colors) >> 62, 0b10);
+		assert_eq!(opaque_count(&colors), 61);
+		assert_eq!(transparent_count(&colors), 1);
+	}
+
+	#[test]
+	fn describe_lists_every_slot() {
+		let colors = read_palette(fixture::palette().as_bytes()).unwrap();
+		let table = describe_colorset(&colors);
+		let lines: Vec<&str> = table.lines().collect();
+		assert_eq!(lines.len(), 65);
+		assert_eq!(lines[0], "Index  Hex        Alpha");
+		// With the `ansi` feature, rows end with a swatch.
+		assert!(lines[1].starts_with("    0  #E50000FF  opaque     "));
+		assert!(lines[7].starts_with("    6  #E500007F  transparent"));
+		assert!(lines[8].starts_with("    7  #FFFFFF00  invisible  "));
+		assert!(lines[64].starts_with("   63  #808080FF  opaque     "));
+	}
+
+	#[test]
+	fn diff_against_itself_is_empty() {
+		let colors = read_palette(fixture::palette().as_bytes()).unwrap();
+		assert!(colorset_diff(&colors, &colors).is_empty());
+	}
+
+	#[test]
+	fn diff_lists_changed_slots() {
+		let before = read_palette(fixture::palette().as_bytes()).unwrap();
+		let mut after = before;
+		after[3] = (0.0, 0.0, 1.0, 1.0);
+		after[40].3 = 0.5;
+
+		assert_eq!(
+			colorset_diff(&before, &after),
+			vec![
+				(3, before[3], (0.0, 0.0, 1.0, 1.0)),
+				(40, (0.5, 0.5, 0.5, 1.0), (0.5, 0.5, 0.5, 0.5)),
+			]
+		);
+	}
+
+	#[test]
+	fn contrast_ratios() {
+		let black = (0.0, 0.0, 0.0, 1.0);
+		let white = (1.0, 1.0, 1.0, 1.0);
+		assert!((contrast_ratio(black, white) - 21.0).abs() < 1e-4);
+		assert_eq!(contrast_ratio(white, black), contrast_ratio(black, white));
+		assert_eq!(contrast_ratio((0.3, 0.6, 0.9, 0.5), (0.3, 0.6, 0.9, 0.5)), 1.0);
+
+		let colors = read_palette(fixture::palette().as_bytes()).unwrap();
+		let matrix = contrast_matrix(&colors);
+		for (i, row) in matrix.iter().enumerate() {
+			assert_eq!(row[i], 1.0);
+			for (j, &ratio) in row.iter().enumerate() {
+				assert_eq!(ratio, matrix[j][i]);
+				assert!(ratio >= 1.0);
+			}
+		}
+		// White against black.
+		assert!((matrix[4][5] - 21.0).abs() < 1e-4);
+	}
+}
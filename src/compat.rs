@@ -0,0 +1,138 @@
+//! Interoperability with Blockland's scripting language.
+//!
+//! Scripts generated here are meant to be executed on a Blockland server.
+//! Datablocks are looked up by `uiName` in `$uiNameTable`, which
+//! [`torque_script_preamble`](fn.torque_script_preamble.html) fills in.
+
+use crate::Brick;
+use std::fmt::Write;
+
+/// The brick group generated bricks are added to.
+const GROUP: &str = "$BlSave::Group";
+
+/// Script to run before the bricks: builds the `uiName` lookup table and
+/// deletes all public bricks.
+pub fn torque_script_preamble() -> String {
+	format!(
+		"createUINameTable();\n{group} = BrickGroup_888888;\n{group}.deleteAll();\n",
+		group = GROUP,
+	)
+}
+
+impl<S: AsRef<str>> Brick<S> {
+	/// Generate TorqueScript that creates and plants the brick, adding it
+	/// to the public brick group. Run
+	/// [`torque_script_preamble`](compat/fn.torque_script_preamble.html)
+	/// first.
+	///
+	/// `angle` becomes an axis-angle `rotation` about Z, as Blockland uses.
+	/// Prints, the owner and other extra data are not included.
+	pub fn to_torque_script(&self) -> String {
+		let base = &self.base;
+		let mut script = String::new();
+
+		writeln!(script, "$b = new fxDTSBrick() {{").unwrap();
+		writeln!(
+			script,
+			"\tdataBlock = $uiNameTable[\"{}\"];",
+			escape(base.ui_name.as_ref())
+		)
+		.unwrap();
+		writeln!(
+			script,
+			"\tposition = \"{} {} {}\";",
+			base.position.0, base.position.1, base.position.2
+		)
+		.unwrap();
+		writeln!(script, "\trotation = \"{}\";", rotation(base.angle)).unwrap();
+		writeln!(script, "\tangleID = \"{}\";", base.angle).unwrap();
+		writeln!(script, "\tcolorID = \"{}\";", base.color_index).unwrap();
+		writeln!(script, "\tcolorFxID = \"{}\";", base.color_fx).unwrap();
+		writeln!(script, "\tshapeFxID = \"{}\";", base.shape_fx).unwrap();
+		writeln!(script, "\tisPlanted = \"1\";").unwrap();
+		writeln!(script, "}};").unwrap();
+		writeln!(script, "{}.add($b);", GROUP).unwrap();
+		writeln!(script, "$b.setTrusted(1);").unwrap();
+		writeln!(script, "$b.plant();").unwrap();
+		writeln!(script, "$b.setRayCasting({});", u8::from(base.raycasting)).unwrap();
+		writeln!(script, "$b.setColliding({});", u8::from(base.collision)).unwrap();
+		writeln!(script, "$b.setRendering({});", u8::from(base.rendering)).unwrap();
+
+		script
+	}
+}
+
+fn rotation(angle: u8) -> &'static str {
+	match angle % 4 {
+		0 => "1 0 0 0",
+		1 => "0 0 1 90",
+		2 => "0 0 1 180",
+		_ => "0 0 -1 90",
+	}
+}
+
+/// Escape a string for use inside a TorqueScript string literal.
+fn escape(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'\\' => escaped.push_str("\\\\"),
+			'"' => escaped.push_str("\\\""),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	#[test]
+	fn preamble_matches_reference() {
+		assert_eq!(
+			torque_script_preamble(),
+			"createUINameTable();\n\
+			 $BlSave::Group = BrickGroup_888888;\n\
+			 $BlSave::Group.deleteAll();\n"
+		);
+	}
+
+	#[test]
+	fn brick_script_matches_reference() {
+		let bricks = fixture::read(&fixture::save(&["2x2 Plate\" 10 0 4 1 0 5  3 2 0 1 0"]));
+		assert_eq!(
+			bricks[0].to_torque_script(),
+			"$b = new fxDTSBrick() {\n\
+			 \tdataBlock = $uiNameTable[\"2x2 Plate\"];\n\
+			 \tposition = \"10 0 4\";\n\
+			 \trotation = \"0 0 1 90\";\n\
+			 \tangleID = \"1\";\n\
+			 \tcolorID = \"5\";\n\
+			 \tcolorFxID = \"3\";\n\
+			 \tshapeFxID = \"2\";\n\
+			 \tisPlanted = \"1\";\n\
+			 };\n\
+			 $BlSave::Group.add($b);\n\
+			 $b.setTrusted(1);\n\
+			 $b.plant();\n\
+			 $b.setRayCasting(0);\n\
+			 $b.setColliding(1);\n\
+			 $b.setRendering(0);\n"
+		);
+	}
+
+	#[test]
+	fn rotations_and_escaping() {
+		let rotations: Vec<&str> = (0..5).map(rotation).collect();
+		assert_eq!(
+			rotations,
+			vec!["1 0 0 0", "0 0 1 90", "0 0 1 180", "0 0 -1 90", "1 0 0 0"]
+		);
+		assert_eq!(escape("a \"b\"\\c\n"), "a \\\"b\\\"\\\\c\\n");
+	}
+}
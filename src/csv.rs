@@ -0,0 +1,216 @@
+//! Export bricks to CSV and read them back.
+//!
+//! Rows contain the [`BrickBase`](../struct.BrickBase.html) fields in the
+//! order of [`HEADER`](constant.HEADER.html). Booleans are written as `0`
+//! and `1`, as in save files. Fields containing commas, quotes or line
+//! breaks are quoted as described in RFC 4180.
+
+use crate::{Brick, BrickBase};
+use std::io::{self, prelude::*};
+
+/// The header row written by [`write_csv`](fn.write_csv.html).
+pub const HEADER: &str = "ui_name,x,y,z,angle,is_baseplate,color_index,print,color_fx,shape_fx,raycasting,collision,rendering";
+
+impl<S: AsRef<str>> Brick<S> {
+	/// Format the base brick data as a CSV row, without a line ending.
+	/// Extra data is not included.
+	pub fn to_csv_row(&self) -> String {
+		let base = &self.base;
+		[
+			quote(base.ui_name.as_ref()),
+			base.position.0.to_string(),
+			base.position.1.to_string(),
+			base.position.2.to_string(),
+			base.angle.to_string(),
+			u8::from(base.is_baseplate).to_string(),
+			base.color_index.to_string(),
+			quote(base.print.as_ref()),
+			base.color_fx.to_string(),
+			base.shape_fx.to_string(),
+			u8::from(base.raycasting).to_string(),
+			u8::from(base.collision).to_string(),
+			u8::from(base.rendering).to_string(),
+		]
+		.join(",")
+	}
+}
+
+/// Write a header row followed by one row per brick.
+pub fn write_csv<'a>(
+	writer: &mut impl Write,
+	bricks: impl Iterator<Item = &'a Brick>,
+) -> io::Result<()> {
+	write!(writer, "{}\r\n", HEADER)?;
+	for brick in bricks {
+		write!(writer, "{}\r\n", brick.to_csv_row())?;
+	}
+	Ok(())
+}
+
+/// Read bricks from CSV in the format written by
+/// [`write_csv`](fn.write_csv.html). The first row is assumed to be the
+/// header and is skipped.
+pub fn read_csv(reader: impl BufRead) -> impl Iterator<Item = io::Result<BrickBase>> {
+	Records(reader)
+		.skip(1)
+		.map(|record| record.and_then(|fields| parse_record(&fields)))
+}
+
+fn quote(field: &str) -> String {
+	if field.contains(&[',', '"', '\r', '\n'][..]) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		String::from(field)
+	}
+}
+
+/// Splits the input into records of unquoted fields. Line breaks inside
+/// quoted fields are kept exactly as they appear in the input.
+struct Records<R>(R);
+
+impl<R: BufRead> Iterator for Records<R> {
+	type Item = io::Result<Vec<String>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut record = String::new();
+		match self.0.read_line(&mut record) {
+			Ok(0) => return None,
+			Ok(_) => {}
+			Err(e) => return Some(Err(e)),
+		}
+
+		while in_quotes(&record) {
+			match self.0.read_line(&mut record) {
+				Ok(0) => return Some(Err(invalid_csv("Unterminated quoted field"))),
+				Ok(_) => {}
+				Err(e) => return Some(Err(e)),
+			}
+		}
+
+		if record.ends_with('\n') {
+			record.pop();
+			if record.ends_with('\r') {
+				record.pop();
+			}
+		}
+
+		Some(Ok(split_record(&record)))
+	}
+}
+
+fn split_record(record: &str) -> Vec<String> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut chars = record.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if in_quotes => {
+				if chars.peek() == Some(&'"') {
+					chars.next();
+					field.push('"');
+				} else {
+					in_quotes = false;
+				}
+			}
+			'"' => in_quotes = true,
+			',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+			c => field.push(c),
+		}
+	}
+
+	fields.push(field);
+	fields
+}
+
+fn parse_record(fields: &[String]) -> io::Result<BrickBase> {
+	if fields.len() != 13 {
+		return Err(invalid_csv("Wrong number of fields in CSV row"));
+	}
+
+	Ok(BrickBase {
+		ui_name: fields[0].clone(),
+		position: (
+			parse_field(&fields[1])?,
+			parse_field(&fields[2])?,
+			parse_field(&fields[3])?,
+		),
+		angle: parse_field(&fields[4])?,
+		is_baseplate: parse_bool(&fields[5])?,
+		color_index: parse_field(&fields[6])?,
+		print: fields[7].clone(),
+		color_fx: parse_field(&fields[8])?,
+		shape_fx: parse_field(&fields[9])?,
+		raycasting: parse_bool(&fields[10])?,
+		collision: parse_bool(&fields[11])?,
+		rendering: parse_bool(&fields[12])?,
+	})
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str) -> io::Result<T> {
+	field
+		.trim()
+		.parse()
+		.map_err(|_| invalid_csv("Invalid number in CSV row"))
+}
+
+fn parse_bool(field: &str) -> io::Result<bool> {
+	match field.trim() {
+		"1" | "true" => Ok(true),
+		"0" | "false" => Ok(false),
+		_ => Err(invalid_csv("Invalid boolean in CSV row")),
+	}
+}
+
+/// Whether a quoted field is still open at the end of `record`.
+// `usize::is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+fn in_quotes(record: &str) -> bool {
+	record.matches('"').count() % 2 != 0
+}
+
+fn invalid_csv(error: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture::brick;
+
+	fn round_trip(bricks: &[Brick]) -> Vec<BrickBase> {
+		let mut csv = Vec::new();
+		write_csv(&mut csv, bricks.iter()).unwrap();
+		read_csv(&csv[..]).collect::<io::Result<_>>().unwrap()
+	}
+
+	#[test]
+	fn special_characters_round_trip() {
+		let mut bricks = Vec::new();
+		for name in &["1x1, round", "Say \"hi\"", "a,\"b\"\r\nc", "Two\nlines"] {
+			bricks.push(brick(name, (1.5, -0.25, 0.2)));
+		}
+		bricks[0].base.print = String::from("Letters/A,B");
+
+		let read = round_trip(&bricks);
+		assert_eq!(read.len(), bricks.len());
+		for (read, brick) in read.iter().zip(&bricks) {
+			assert_eq!(read, &brick.base);
+		}
+	}
+
+	#[test]
+	fn rows_match_header() {
+		let row = brick("1x1, round", (1.0, 2.0, 0.2)).to_csv_row();
+		assert_eq!(row, "\"1x1, round\",1,2,0.2,0,0,0,,0,0,1,1,1");
+		assert_eq!(HEADER.split(',').count(), 13);
+	}
+
+	#[test]
+	fn unterminated_quote_is_an_error() {
+		let csv = format!("{}\r\n\"1x1,0,0,0,0,0,0,,0,0,1,1,1\r\n", HEADER);
+		let result: io::Result<Vec<_>> = read_csv(csv.as_bytes()).collect();
+		assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+	}
+}
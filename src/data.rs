@@ -1,8 +1,34 @@
+use std::{
+	collections::HashMap,
+	fmt,
+	hash::{Hash, Hasher},
+	io,
+};
+
+/// An RGBA color with components ranging from `0.0` through `1.0`.
+pub type Color = (f32, f32, f32, f32);
+
+/// The 64 colors of a colorset, indexed by
+/// [`BrickBase::color_index`](struct.BrickBase.html#structfield.color_index).
+pub type Colors = [Color; 64];
+
+/// Tolerance used by [`BrickBase::positionally_eq`](struct.BrickBase.html#method.positionally_eq),
+/// in world units.
+pub const POSITION_EPSILON: f32 = 0.001;
+
+/// Horizontal spacing of the grid brick positions lie on (half a stud).
+pub(crate) const GRID_XY: f32 = 0.25;
+/// Vertical spacing of the grid brick positions lie on (half a plate).
+pub(crate) const GRID_Z: f32 = 0.1;
+
 /// A single brick in a save file, including extended attributes.
 #[derive(Debug, Clone)]
 pub struct Brick<S = String> {
 	/// Basic brick data excluding extended attributes.
 	pub base: BrickBase<S>,
+	/// The owner of the brick, from its `+-OWNER` line. Blockland stores the
+	/// owner's BL_ID here.
+	pub owner: Option<S>,
 	/// Extra brick data associated with this brick but not supported by the
 	/// library.
 	pub unknown_extra: Vec<S>,
@@ -23,7 +49,11 @@ pub struct BrickBase<S = String> {
 	/// Index into the colorset.
 	/// Valid values range from `0` through `63`.
 	pub color_index: u8,
-	/// Name of the print to use for print bricks. "" represents none.
+	/// Name of the print to use for print bricks, such as `Letters/A`.
+	/// "" represents none.
+	///
+	/// Blockland compares print names case-insensitively. Prints may come
+	/// from add-ons, so any name may appear.
 	pub print: S,
 	/// Color effect (such as glow, rainbow).
 	pub color_fx: u8,
@@ -36,3 +66,850 @@ pub struct BrickBase<S = String> {
 	/// Whether the brick is visible.
 	pub rendering: bool,
 }
+
+/// A set of flag changes to apply with
+/// [`BrickBase::set_flags`](struct.BrickBase.html#method.set_flags).
+/// Flags that are `None` are left unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BrickFlags {
+	/// The new value of `raycasting`, if any.
+	pub raycasting: Option<bool>,
+	/// The new value of `collision`, if any.
+	pub collision: Option<bool>,
+	/// The new value of `rendering`, if any.
+	pub rendering: Option<bool>,
+}
+
+impl BrickBase {
+	/// A plain, unrotated 2x2 plate at the origin using color `0`, with no
+	/// print or effects, which can be raycasted against, collided with and
+	/// is rendered.
+	pub fn with_defaults() -> Self {
+		Self {
+			ui_name: String::from("2x2 Plate"),
+			position: (0.0, 0.0, 0.0),
+			angle: 0,
+			is_baseplate: false,
+			color_index: 0,
+			print: String::new(),
+			color_fx: 0,
+			shape_fx: 0,
+			raycasting: true,
+			collision: true,
+			rendering: true,
+		}
+	}
+}
+
+impl<S: fmt::Display> BrickBase<S> {
+	/// Format the brick as it would appear as a line in a save file.
+	pub fn debug_format(&self) -> String {
+		format!(
+			"{}\" {} {} {} {} {} {} {} {} {} {} {} {}",
+			self.ui_name,
+			self.position.0,
+			self.position.1,
+			self.position.2,
+			self.angle,
+			u8::from(self.is_baseplate),
+			self.color_index,
+			self.print,
+			self.color_fx,
+			self.shape_fx,
+			u8::from(self.raycasting),
+			u8::from(self.collision),
+			u8::from(self.rendering),
+		)
+	}
+}
+
+impl<S: fmt::Debug> BrickBase<S> {
+	/// Format the brick with every field labeled on its own line, for
+	/// debugging output.
+	pub fn debug_string(&self) -> String {
+		format!("{:#?}", self)
+	}
+}
+
+impl<S: AsRef<str>> BrickBase<S> {
+	/// A deterministic identifier derived from the `ui_name`, position and
+	/// `angle` of the brick, using the FNV-1a hash.
+	///
+	/// Bricks with the same `ui_name`, position and `angle` share an
+	/// identifier. The value is stable across runs and platforms.
+	pub fn unique_id(&self) -> u64 {
+		const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+		const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+		let position = [self.position.0, self.position.1, self.position.2];
+		let bytes = self
+			.ui_name
+			.as_ref()
+			.bytes()
+			.chain(position.iter().flat_map(|c| c.to_bits().to_le_bytes()))
+			.chain(Some(self.angle));
+
+		bytes.fold(OFFSET_BASIS, |hash, byte| {
+			(hash ^ u64::from(byte)).wrapping_mul(PRIME)
+		})
+	}
+
+	/// Whether the brick uses a print, i.e. `print` is not "".
+	pub fn print_is_present(&self) -> bool {
+		!self.print.as_ref().is_empty()
+	}
+
+	/// The name of the print used by the brick, or `None` if `print` is "".
+	pub fn print_name(&self) -> Option<&str> {
+		Some(self.print.as_ref()).filter(|print| !print.is_empty())
+	}
+}
+
+impl<S> BrickBase<S> {
+	/// The position in integer grid units, which avoids comparing floats.
+	pub(crate) fn grid_position(&self) -> (i32, i32, i32) {
+		(
+			(self.position.0 / GRID_XY).round() as i32,
+			(self.position.1 / GRID_XY).round() as i32,
+			(self.position.2 / GRID_Z).round() as i32,
+		)
+	}
+
+	/// Whether the brick is purely decorative: rendered, but neither
+	/// collidable nor raycastable.
+	pub fn is_print_only(&self) -> bool {
+		self.rendering && !self.collision && !self.raycasting
+	}
+
+	/// The rotation of the brick in degrees: `0.0`, `90.0`, `180.0` or
+	/// `270.0`. Only the lowest two bits of `angle` are used.
+	pub fn angle_degrees(&self) -> f32 {
+		f32::from(self.angle % 4) * 90.0
+	}
+
+	/// Set the rotation from degrees. Accepts multiples of 90 degrees,
+	/// including negative ones and ones beyond a full turn, within a
+	/// tolerance of less than one degree.
+	pub fn set_angle_from_degrees(&mut self, degrees: f32) -> io::Result<()> {
+		let quarters = (degrees / 90.0).round();
+		if !degrees.is_finite() || (degrees - quarters * 90.0).abs() >= 1.0 {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"Angle is not a multiple of 90 degrees",
+			));
+		}
+		self.angle = quarters.rem_euclid(4.0) as u8;
+		Ok(())
+	}
+
+	/// Like [`set_angle_from_degrees`](#method.set_angle_from_degrees), but
+	/// consumes and returns the brick.
+	pub fn with_angle_degrees(mut self, degrees: f32) -> io::Result<Self> {
+		self.set_angle_from_degrees(degrees)?;
+		Ok(self)
+	}
+
+	/// Apply the flags of `flags` that are `Some`.
+	pub fn set_flags(&mut self, flags: BrickFlags) {
+		if let Some(raycasting) = flags.raycasting {
+			self.raycasting = raycasting;
+		}
+		if let Some(collision) = flags.collision {
+			self.collision = collision;
+		}
+		if let Some(rendering) = flags.rendering {
+			self.rendering = rendering;
+		}
+	}
+
+	/// Whether the positions of two bricks are equal within
+	/// [`POSITION_EPSILON`](constant.POSITION_EPSILON.html) on each axis.
+	///
+	/// Unlike `==`, which compares positions bit for bit, this tolerates
+	/// rounding differences.
+	pub fn positionally_eq<T>(&self, other: &BrickBase<T>) -> bool {
+		self.position_eq_approx(other, POSITION_EPSILON)
+	}
+
+	/// Whether the positions of two bricks are equal within `epsilon` on
+	/// each axis. Always `false` if either position has a NaN component.
+	pub fn position_eq_approx<T>(&self, other: &BrickBase<T>, epsilon: f32) -> bool {
+		(self.position.0 - other.position.0).abs() <= epsilon
+			&& (self.position.1 - other.position.1).abs() <= epsilon
+			&& (self.position.2 - other.position.2).abs() <= epsilon
+	}
+
+	/// The Euclidean distance between the positions of two bricks.
+	/// Infinite if either position has a NaN component.
+	pub fn distance_to<T>(&self, other: &BrickBase<T>) -> f32 {
+		let distance = ((self.position.0 - other.position.0).powi(2)
+			+ (self.position.1 - other.position.1).powi(2)
+			+ (self.position.2 - other.position.2).powi(2))
+		.sqrt();
+		if distance.is_nan() {
+			f32::INFINITY
+		} else {
+			distance
+		}
+	}
+
+	/// Look up the color of the brick in a colorset.
+	///
+	/// Returns transparent black if `color_index` is out of range.
+	pub fn resolve_color<'a>(&self, colorset: &'a Colors) -> &'a Color {
+		static OUT_OF_RANGE: Color = (0.0, 0.0, 0.0, 0.0);
+		colorset
+			.get(usize::from(self.color_index))
+			.unwrap_or(&OUT_OF_RANGE)
+	}
+
+	/// Whether the brick is rendered with a color that isn't fully
+	/// transparent.
+	///
+	/// ```rust,no_run
+	/// # use std::{fs::File, io::BufReader};
+	/// # fn main() -> std::io::Result<()> {
+	/// let reader = bl_save::Reader::new(BufReader::new(File::open("House.bls")?))?;
+	/// let colors = *reader.colors();
+	///
+	/// for brick in reader {
+	///     let brick = brick?;
+	///     if !brick.base.is_visible(&colors) {
+	///         continue;
+	///     }
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn is_visible(&self, colorset: &Colors) -> bool {
+		self.rendering && !self.is_fully_transparent(colorset)
+	}
+
+	/// Whether the brick's color is fully transparent, making it invisible
+	/// even when `rendering` is set. Any alpha above `0.0` counts as
+	/// visible.
+	pub fn is_fully_transparent(&self, colorset: &Colors) -> bool {
+		self.resolve_color(colorset).3 <= 0.0
+	}
+
+	/// Whether the brick can't be seen, either because `rendering` is off
+	/// or because its color is fully transparent. The opposite of
+	/// [`is_visible`](#method.is_visible).
+	pub fn is_effectively_invisible(&self, colorset: &Colors) -> bool {
+		!self.is_visible(colorset)
+	}
+
+	/// Whether the brick should be treated as passable: `collision` is off
+	/// or the brick is [effectively invisible](#method.is_effectively_invisible).
+	pub fn is_passable(&self, colorset: &Colors) -> bool {
+		!self.collision || self.is_effectively_invisible(colorset)
+	}
+
+	/// The WCAG contrast ratio between the colors of two bricks, as
+	/// computed by [`colorset::contrast_ratio`](colorset/fn.contrast_ratio.html).
+	pub fn color_contrast_ratio<T>(&self, other: &BrickBase<T>, colorset: &Colors) -> f32 {
+		crate::colorset::contrast_ratio(
+			*self.resolve_color(colorset),
+			*other.resolve_color(colorset),
+		)
+	}
+
+	fn position_bits(&self) -> (u32, u32, u32) {
+		(
+			self.position.0.to_bits(),
+			self.position.1.to_bits(),
+			self.position.2.to_bits(),
+		)
+	}
+}
+
+impl<S: Clone> BrickBase<S> {
+	/// Return a copy of the brick with its color index changed to
+	/// `new_index` if it is currently `old_index`.
+	pub fn apply_palette_swap(&self, old_index: u8, new_index: u8) -> Self {
+		let mut brick = self.clone();
+		if brick.color_index == old_index {
+			brick.color_index = new_index;
+		}
+		brick
+	}
+}
+
+impl<S: PartialEq + Clone> Brick<S> {
+	/// Combine the extra data of two bricks, keeping the base of `self`.
+	///
+	/// The result holds the lines of `self` followed by the lines of
+	/// `other`, in their original order, with repeated lines removed after
+	/// their first occurrence. The owner of `self` is kept if it has one.
+	pub fn merge_extras(self, other: &Brick<S>) -> Self {
+		let mut unknown_extra = Vec::with_capacity(self.unknown_extra.len());
+		for line in self.unknown_extra.into_iter().chain(other.unknown_extra.iter().cloned()) {
+			if !unknown_extra.contains(&line) {
+				unknown_extra.push(line);
+			}
+		}
+		Self {
+			base: self.base,
+			owner: self.owner.or_else(|| other.owner.clone()),
+			unknown_extra,
+		}
+	}
+}
+
+impl Brick {
+	/// Set the owner of the brick, replacing any existing owner.
+	pub fn set_owner(mut self, owner: &str) -> Self {
+		self.owner = Some(String::from(owner));
+		self
+	}
+}
+
+impl<S: fmt::Debug> Brick<S> {
+	/// Format the brick with every field labeled on its own line, including
+	/// the extra data lines, for debugging output.
+	pub fn debug_string(&self) -> String {
+		format!("{:#?}", self)
+	}
+}
+
+impl<S: AsRef<str>> Brick<S> {
+	/// Group the extra data lines by key.
+	///
+	/// Each line has the form `+-KEY value`, where the key ends at the first
+	/// space or tab. Lines with no value map to "", and lines without the
+	/// `+-` prefix are ignored. Keys are compared ignoring ASCII case and
+	/// grouped under the spelling that appears first. Values keep the order
+	/// of their lines.
+	///
+	/// The owner is parsed into [`owner`](#structfield.owner) instead of
+	/// being kept as an extra data line.
+	pub fn extras_map(&self) -> HashMap<&str, Vec<&str>> {
+		let mut map: HashMap<&str, Vec<&str>> = HashMap::new();
+		for (key, value) in self.extras() {
+			let existing = map.keys().copied().find(|other| other.eq_ignore_ascii_case(key));
+			map.entry(existing.unwrap_or(key)).or_default().push(value);
+		}
+		map
+	}
+
+	/// The owner of the brick, if any.
+	pub fn owner(&self) -> Option<&str> {
+		self.owner.as_ref().map(AsRef::as_ref)
+	}
+
+	/// The value of the first extra data line with the given key, ignoring
+	/// ASCII case. See [`extras_map`](#method.extras_map).
+	pub fn get_extra(&self, key: &str) -> Option<&str> {
+		self.extras()
+			.find(|(other, _)| other.eq_ignore_ascii_case(key))
+			.map(|(_, value)| value)
+	}
+
+	pub(crate) fn extras(&self) -> impl Iterator<Item = (&str, &str)> {
+		self.unknown_extra.iter().filter_map(|line| {
+			let line = line.as_ref().strip_prefix("+-")?;
+			Some(line.split_once([' ', '\t']).unwrap_or((line, "")))
+		})
+	}
+}
+
+impl<S> Brick<S> {
+	/// Remove all extra data from the brick, including the owner.
+	pub fn without_extras(mut self) -> Self {
+		self.owner = None;
+		self.unknown_extra.clear();
+		self
+	}
+
+	/// Remove the owner of the brick.
+	pub fn clear_owner(mut self) -> Self {
+		self.owner = None;
+		self
+	}
+}
+
+/// Positions are compared bit for bit, so `0.0` and `-0.0` differ and a
+/// `NaN` equals an identical `NaN`. This keeps `Eq` consistent with `Hash`.
+impl<S: PartialEq> PartialEq for BrickBase<S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.ui_name == other.ui_name
+			&& self.position_bits() == other.position_bits()
+			&& self.angle == other.angle
+			&& self.is_baseplate == other.is_baseplate
+			&& self.color_index == other.color_index
+			&& self.print == other.print
+			&& self.color_fx == other.color_fx
+			&& self.shape_fx == other.shape_fx
+			&& self.raycasting == other.raycasting
+			&& self.collision == other.collision
+			&& self.rendering == other.rendering
+	}
+}
+
+impl<S: Eq> Eq for BrickBase<S> {}
+
+impl<S: Hash> Hash for BrickBase<S> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.ui_name.hash(state);
+		self.position_bits().hash(state);
+		self.angle.hash(state);
+		self.is_baseplate.hash(state);
+		self.color_index.hash(state);
+		self.print.hash(state);
+		self.color_fx.hash(state);
+		self.shape_fx.hash(state);
+		self.raycasting.hash(state);
+		self.collision.hash(state);
+		self.rendering.hash(state);
+	}
+}
+
+impl<S: fmt::Display> fmt::Display for BrickBase<S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"\"{}\" @ ({:?}, {:?}, {:?}) angle={}\u{b0} color={}",
+			self.ui_name,
+			self.position.0,
+			self.position.1,
+			self.position.2,
+			u32::from(self.angle % 4) * 90,
+			self.color_index,
+		)?;
+
+		let mut effects = Vec::new();
+		match self.color_fx {
+			0 => {}
+			fx => effects.push(color_fx_name(fx)),
+		}
+		match self.shape_fx {
+			0 => {}
+			fx => effects.push(shape_fx_name(fx)),
+		}
+		if !effects.is_empty() {
+			write!(f, " [{}]", effects.join(", "))?;
+		}
+
+		Ok(())
+	}
+}
+
+impl<S: fmt::Display> fmt::Display for Brick<S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.base)?;
+		if !self.unknown_extra.is_empty() {
+			write!(f, " (+{} extra)", self.unknown_extra.len())?;
+		}
+		Ok(())
+	}
+}
+
+fn color_fx_name(fx: u8) -> String {
+	match fx {
+		0 => String::from("none"),
+		1 => String::from("pearl"),
+		2 => String::from("chrome"),
+		3 => String::from("glow"),
+		4 => String::from("blink"),
+		5 => String::from("swirl"),
+		6 => String::from("rainbow"),
+		fx => format!("color fx {}", fx),
+	}
+}
+
+fn shape_fx_name(fx: u8) -> String {
+	match fx {
+		0 => String::from("none"),
+		1 => String::from("undulo"),
+		2 => String::from("water"),
+		fx => format!("shape fx {}", fx),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	fn colorset_with_alpha(alpha: f32) -> Colors {
+		let mut colors = [(1.0, 1.0, 1.0, 1.0); 64];
+		colors[1] = (1.0, 1.0, 1.0, alpha);
+		colors
+	}
+
+	#[test]
+	fn visibility_predicates_agree_on_alpha() {
+		let brick = BrickBase {
+			color_index: 1,
+			..BrickBase::with_defaults()
+		};
+		for &alpha in &[0.0, 1e-9, 0.5, 1.0] {
+			let colors = colorset_with_alpha(alpha);
+			let transparent = brick.is_fully_transparent(&colors);
+			assert_eq!(transparent, alpha == 0.0, "alpha {}", alpha);
+			assert_eq!(brick.is_visible(&colors), !transparent, "alpha {}", alpha);
+			assert_eq!(brick.is_effectively_invisible(&colors), transparent, "alpha {}", alpha);
+		}
+	}
+
+	#[test]
+	fn out_of_range_color_is_transparent() {
+		let brick = BrickBase {
+			color_index: 64,
+			..BrickBase::with_defaults()
+		};
+		assert!(brick.is_fully_transparent(&colorset_with_alpha(1.0)));
+	}
+
+	#[test]
+	fn set_owner_adds_or_replaces() {
+		let brick = Brick {
+			base: BrickBase::with_defaults(),
+			owner: None,
+			unknown_extra: vec![String::from("+-NTOBJECTNAME _door")],
+		};
+
+		let brick = brick.set_owner("5");
+		assert_eq!(brick.owner(), Some("5"));
+
+		let brick = brick.set_owner("6");
+		assert_eq!(brick.owner(), Some("6"));
+		assert_eq!(brick.unknown_extra, vec!["+-NTOBJECTNAME _door"]);
+
+		assert_eq!(brick.clear_owner().owner(), None);
+	}
+
+	#[test]
+	fn display_is_a_one_line_summary() {
+		let mut brick = Brick {
+			base: BrickBase {
+				position: (10.0, 0.0, 4.0),
+				angle: 1,
+				color_index: 5,
+				color_fx: 3,
+				..BrickBase::with_defaults()
+			},
+			owner: None,
+			unknown_extra: Vec::new(),
+		};
+		assert_eq!(
+			brick.to_string(),
+			"\"2x2 Plate\" @ (10.0, 0.0, 4.0) angle=90\u{b0} color=5 [glow]"
+		);
+
+		brick.base.angle = 5;
+		brick.base.shape_fx = 2;
+		brick.unknown_extra.push(String::from("+-NTOBJECTNAME _door"));
+		assert_eq!(
+			brick.to_string(),
+			"\"2x2 Plate\" @ (10.0, 0.0, 4.0) angle=90\u{b0} color=5 [glow, water] (+1 extra)"
+		);
+	}
+
+	#[test]
+	fn hash_set_deduplicates_identical_bricks() {
+		let a = BrickBase::with_defaults();
+		let mut moved = a.clone();
+		moved.position.0 = 0.5;
+		let mut negative_zero = a.clone();
+		negative_zero.position.0 = -0.0;
+
+		let mut set = std::collections::HashSet::new();
+		assert!(set.insert(a.clone()));
+		assert!(!set.insert(a.clone()));
+		assert!(set.insert(moved));
+		// Positions compare bit for bit.
+		assert!(set.insert(negative_zero.clone()));
+		assert_eq!(set.len(), 3);
+		assert!(a.positionally_eq(&negative_zero));
+	}
+
+	#[test]
+	fn positional_equality_tolerates_rounding() {
+		let a = BrickBase::with_defaults();
+		let mut b = a.clone();
+		b.position = (0.000_5, -0.000_5, 0.001);
+		assert!(a.positionally_eq(&b));
+		assert_ne!(a, b);
+		b.position.2 = 0.002;
+		assert!(!a.positionally_eq(&b));
+	}
+
+	#[test]
+	fn resolve_color_at_range_edges() {
+		let mut colors = colorset_with_alpha(1.0);
+		colors[63] = (0.1, 0.2, 0.3, 0.4);
+		let mut brick = BrickBase {
+			color_index: 63,
+			..BrickBase::with_defaults()
+		};
+		assert_eq!(*brick.resolve_color(&colors), (0.1, 0.2, 0.3, 0.4));
+		assert!(brick.is_visible(&colors));
+
+		brick.color_index = 64;
+		assert_eq!(*brick.resolve_color(&colors), (0.0, 0.0, 0.0, 0.0));
+		assert!(!brick.is_visible(&colors));
+
+		brick.color_index = 0;
+		brick.rendering = false;
+		assert!(!brick.is_visible(&colors));
+	}
+
+	#[test]
+	fn invisible_when_hidden_or_transparent() {
+		let colors = colorset_with_alpha(0.0);
+		let visible = BrickBase::with_defaults();
+		assert!(!visible.is_effectively_invisible(&colors));
+		assert!(!visible.is_passable(&colors));
+
+		let mut hidden = visible.clone();
+		hidden.rendering = false;
+		assert!(hidden.is_effectively_invisible(&colors));
+
+		let mut transparent = visible.clone();
+		transparent.color_index = 1;
+		assert!(transparent.is_effectively_invisible(&colors));
+
+		let mut out_of_range = visible.clone();
+		out_of_range.color_index = 64;
+		assert!(out_of_range.is_effectively_invisible(&colors));
+
+		transparent.rendering = false;
+		assert!(transparent.is_effectively_invisible(&colors));
+		for brick in &[hidden, transparent, out_of_range] {
+			assert!(brick.is_passable(&colors));
+		}
+	}
+
+	#[test]
+	fn passable_without_collision() {
+		let colors = colorset_with_alpha(1.0);
+		let mut brick = BrickBase::with_defaults();
+		brick.collision = false;
+		assert!(!brick.is_effectively_invisible(&colors));
+		assert!(brick.is_passable(&colors));
+	}
+
+	#[test]
+	fn merge_extras_keeps_order_and_drops_repeats() {
+		let extras = |lines: &[&str]| Brick {
+			base: BrickBase::with_defaults(),
+			owner: None,
+			unknown_extra: lines.iter().map(|&line| String::from(line)).collect(),
+		};
+		let a = extras(&["+-NTOBJECTNAME _door", "+-EMITTER Fire\" 0"]).set_owner("5");
+		let b = extras(&["+-EMITTER Fire\" 0", "+-LIGHT Red\" 1"]).set_owner("6");
+
+		let merged = a.clone().merge_extras(&b);
+		assert_eq!(merged.base, a.base);
+		assert_eq!(merged.owner(), Some("5"));
+		assert_eq!(
+			merged.unknown_extra,
+			vec!["+-NTOBJECTNAME _door", "+-EMITTER Fire\" 0", "+-LIGHT Red\" 1"]
+		);
+
+		let c = extras(&["+-ITEM Gun\" 0 2 1000"]);
+		let merged = c.merge_extras(&b);
+		assert_eq!(merged.owner(), Some("6"));
+		assert_eq!(
+			merged.unknown_extra,
+			vec!["+-ITEM Gun\" 0 2 1000", "+-EMITTER Fire\" 0", "+-LIGHT Red\" 1"]
+		);
+
+		let cleared = merged.without_extras();
+		assert_eq!(cleared.owner(), None);
+		assert!(cleared.unknown_extra.is_empty());
+	}
+
+	#[test]
+	fn extras_map_groups_repeated_keys() {
+		let save = fixture::save(&[
+			"1x1\" 0 0 0.2 0 0 0  0 0 1 1 1",
+			"+-OWNER 5",
+			"+-EVENT\t0\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+			"+-NTOBJECTNAME _door",
+			"+-event\t1\t1\tonActivate\t0\tSelf\t\tfireRelay",
+			"+-EMITTER",
+		]);
+		let mut bricks = fixture::read(&save);
+		let brick = &mut bricks[0];
+		brick.unknown_extra.push(String::from("not an extra"));
+
+		let map = brick.extras_map();
+		assert_eq!(map.len(), 3);
+		assert_eq!(
+			map["EVENT"],
+			vec![
+				"0\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+				"1\t1\tonActivate\t0\tSelf\t\tfireRelay"
+			]
+		);
+		assert_eq!(map["NTOBJECTNAME"], vec!["_door"]);
+		assert_eq!(map["EMITTER"], vec![""]);
+		assert_eq!(brick.owner(), Some("5"));
+
+		assert_eq!(brick.get_extra("Event"), Some("0\t1\tonActivate\t0\tSelf\t\tsetColor\t3"));
+		assert_eq!(brick.get_extra("ntobjectname"), Some("_door"));
+		assert_eq!(brick.get_extra("OWNER"), None);
+		assert_eq!(brick.get_extra("LIGHT"), None);
+	}
+
+	#[test]
+	fn empty_print_means_no_print() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("1x2F Print", (0.0, 0.0, 0.2)),
+			"1x2F Print\" 1 0 0.2 0 0 0 Letters/A 0 0 1 1 1",
+		]));
+		assert!(!bricks[0].base.print_is_present());
+		assert_eq!(bricks[0].base.print_name(), None);
+		assert!(bricks[1].base.print_is_present());
+		assert_eq!(bricks[1].base.print_name(), Some("Letters/A"));
+	}
+
+	#[test]
+	fn angle_degrees_round_trip() {
+		let mut brick = BrickBase::with_defaults();
+		for angle in 0..4 {
+			brick.angle = angle;
+			let degrees = brick.angle_degrees();
+			assert_eq!(degrees, f32::from(angle) * 90.0);
+			brick.angle = 0;
+			brick.set_angle_from_degrees(degrees).unwrap();
+			assert_eq!(brick.angle, angle);
+		}
+
+		brick.set_angle_from_degrees(89.5).unwrap();
+		assert_eq!(brick.angle, 1);
+		brick.set_angle_from_degrees(-90.0).unwrap();
+		assert_eq!(brick.angle, 3);
+		brick.set_angle_from_degrees(450.0).unwrap();
+		assert_eq!(brick.angle, 1);
+
+		for &degrees in &[91.0, 45.0, f32::NAN, f32::INFINITY] {
+			let error = brick.set_angle_from_degrees(degrees).unwrap_err();
+			assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+			assert_eq!(brick.angle, 1);
+		}
+
+		let brick = BrickBase::with_defaults().with_angle_degrees(180.0).unwrap();
+		assert_eq!(brick.angle, 2);
+		assert!(BrickBase::with_defaults().with_angle_degrees(91.0).is_err());
+	}
+
+	#[test]
+	fn approximate_position_and_distance() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("1x1", (1.0, 2.0, 3.0)),
+			&fixture::line("1x1", (1.0 + f32::EPSILON * 2.0, 2.0, 3.0)),
+			&fixture::line("1x1", (4.0, 6.0, 3.0)),
+		]));
+		let (a, b, c) = (&bricks[0].base, &bricks[1].base, &bricks[2].base);
+		assert!(a.position_eq_approx(b, 0.001));
+		assert!(!a.position_eq_approx(c, 0.001));
+		assert!(a.position_eq_approx(c, 4.0));
+		assert_eq!(a.distance_to(c), 5.0);
+
+		let mut nan = a.clone();
+		nan.position.1 = f32::NAN;
+		assert!(!nan.position_eq_approx(&nan, f32::INFINITY));
+		assert!(!a.position_eq_approx(&nan, f32::INFINITY));
+		assert_eq!(a.distance_to(&nan), f32::INFINITY);
+	}
+
+	#[test]
+	fn color_contrast_between_bricks() {
+		let mut colors = colorset_with_alpha(1.0);
+		colors[2] = (0.0, 0.0, 0.0, 1.0);
+		let white = BrickBase::with_defaults();
+		let black = BrickBase {
+			color_index: 2,
+			..BrickBase::with_defaults()
+		};
+		assert!((white.color_contrast_ratio(&black, &colors) - 21.0).abs() < 1e-4);
+		assert_eq!(white.color_contrast_ratio(&white, &colors), 1.0);
+	}
+
+	#[test]
+	fn default_brick_is_a_parseable_line() {
+		assert_eq!(
+			BrickBase::with_defaults().debug_format(),
+			"2x2 Plate\" 0 0 0 0 0 0  0 0 1 1 1"
+		);
+		assert_eq!(
+			BrickBase::with_defaults().debug_format().parse::<BrickBase>().unwrap(),
+			BrickBase::with_defaults()
+		);
+	}
+
+	#[test]
+	fn print_only_bricks_are_rendered_without_collision_or_raycasting() {
+		let mut brick = BrickBase::with_defaults();
+		assert!(!brick.is_print_only());
+		brick.collision = false;
+		assert!(!brick.is_print_only());
+		brick.raycasting = false;
+		assert!(brick.is_print_only());
+		brick.rendering = false;
+		assert!(!brick.is_print_only());
+	}
+
+	#[test]
+	fn unique_id_depends_on_name_position_and_angle() {
+		let a = BrickBase::with_defaults();
+		let mut b = a.clone();
+		b.color_index = 5;
+		b.print = String::from("Letters/A");
+		assert_eq!(a.unique_id(), b.unique_id());
+
+		let mut moved = a.clone();
+		moved.position.0 = 0.5;
+		assert_ne!(a.unique_id(), moved.unique_id());
+		let mut rotated = a.clone();
+		rotated.angle = 1;
+		assert_ne!(a.unique_id(), rotated.unique_id());
+	}
+
+	#[test]
+	fn set_flags_changes_only_given_flags() {
+		let mut brick = BrickBase::with_defaults();
+		brick.set_flags(BrickFlags {
+			collision: Some(false),
+			rendering: Some(true),
+			..BrickFlags::default()
+		});
+		assert_eq!((brick.raycasting, brick.collision, brick.rendering), (true, false, true));
+
+		brick.set_flags(BrickFlags::default());
+		assert_eq!((brick.raycasting, brick.collision, brick.rendering), (true, false, true));
+	}
+
+	#[test]
+	fn palette_swap_changes_only_matching_index() {
+		let brick = BrickBase {
+			color_index: 3,
+			..BrickBase::with_defaults()
+		};
+		let swapped = brick.apply_palette_swap(3, 9);
+		assert_eq!(swapped.color_index, 9);
+		assert_eq!(BrickBase { color_index: 3, ..swapped }, brick);
+		assert_eq!(brick.apply_palette_swap(4, 9), brick);
+	}
+
+	#[test]
+	fn debug_string_labels_every_field() {
+		let base = BrickBase::with_defaults();
+		let debug = base.debug_string();
+		assert!(debug.starts_with("BrickBase {\n    ui_name: \"2x2 Plate\",\n"));
+		assert!(debug.ends_with("    rendering: true,\n}"));
+		assert!(debug.contains("\n    color_index: 0,\n"));
+
+		let brick = Brick {
+			base,
+			owner: Some(String::from("5")),
+			unknown_extra: vec![String::from("+-NTOBJECTNAME _door")],
+		};
+		let debug = brick.debug_string();
+		assert!(debug.contains("\n    owner: Some(\n        \"5\",\n    ),\n"));
+		assert!(debug.contains("\n        \"+-NTOBJECTNAME _door\",\n"));
+	}
+}
@@ -0,0 +1,427 @@
+use crate::{
+	data::{GRID_XY, GRID_Z},
+	Brick,
+};
+use std::collections::HashMap;
+
+type DiffKey<'a> = (&'a str, u8, u8);
+
+/// Groups of moved bricks with more bricks than this on either side are
+/// paired greedily instead of optimally.
+const EXACT_PAIRING_LIMIT: usize = 256;
+
+/// The differences between two sets of bricks, as computed by
+/// [`save_diff`](fn.save_diff.html).
+#[derive(Debug, Clone, Default)]
+pub struct SaveDiff {
+	/// Bricks only in the second set.
+	pub added: Vec<Brick>,
+	/// Bricks only in the first set.
+	pub removed: Vec<Brick>,
+	/// Bricks that changed position, as `(old, new)` pairs.
+	pub moved: Vec<(Brick, Brick)>,
+}
+
+impl SaveDiff {
+	/// Whether the two sets of bricks had no differences.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+	}
+
+	/// A one-line summary, such as `2 added, 0 removed, 1 moved`.
+	pub fn summarize(&self) -> String {
+		format!(
+			"{} added, {} removed, {} moved",
+			self.added.len(),
+			self.removed.len(),
+			self.moved.len()
+		)
+	}
+}
+
+/// Compute which bricks were added, removed or moved going from `a` to `b`.
+///
+/// Bricks are matched by `ui_name`, `angle` and `color_index`. Among bricks
+/// with the same key, those at the same grid position are unchanged, and
+/// the rest are paired so that the total distance moved is as small as
+/// possible; each pair is a move. Bricks left without a partner are added
+/// or removed.
+///
+/// Optimal pairing takes memory quadratic and time cubic in the number of
+/// moved bricks that share a key, so it is only used for groups of up to
+/// 256 bricks. In larger groups, such as when a whole build moved, each
+/// brick is instead paired with the closest brick that is still unpaired,
+/// which is fast but may not minimize the total distance.
+///
+/// Other fields, such as flags and extra data, are not compared.
+pub fn save_diff(a: &[Brick], b: &[Brick]) -> SaveDiff {
+	let mut groups: HashMap<DiffKey<'_>, (Vec<usize>, Vec<usize>)> = HashMap::new();
+	for (index, brick) in a.iter().enumerate() {
+		groups.entry(diff_key(brick)).or_default().0.push(index);
+	}
+	for (index, brick) in b.iter().enumerate() {
+		groups.entry(diff_key(brick)).or_default().1.push(index);
+	}
+
+	let mut paired_a = vec![false; a.len()];
+	let mut paired_b = vec![false; b.len()];
+	let mut moves = Vec::new();
+
+	for (from, to) in groups.values() {
+		// Unchanged bricks first, so they are never counted as moves.
+		// Pairing bricks at the same position never makes the total
+		// distance of the remaining pairs larger.
+		let mut at_position: HashMap<_, Vec<usize>> = HashMap::new();
+		for &i in from {
+			at_position.entry(a[i].base.grid_position()).or_default().push(i);
+		}
+		for &j in to {
+			if let Some(i) = at_position
+				.get_mut(&b[j].base.grid_position())
+				.and_then(Vec::pop)
+			{
+				paired_a[i] = true;
+				paired_b[j] = true;
+			}
+		}
+
+		let from: Vec<usize> = from.iter().copied().filter(|&i| !paired_a[i]).collect();
+		let to: Vec<usize> = to.iter().copied().filter(|&j| !paired_b[j]).collect();
+		for (i, j) in closest_pairs(a, &from, b, &to) {
+			paired_a[i] = true;
+			paired_b[j] = true;
+			moves.push((i, j));
+		}
+	}
+
+	moves.sort_unstable();
+
+	SaveDiff {
+		added: unpaired(b, &paired_b),
+		removed: unpaired(a, &paired_a),
+		moved: moves
+			.into_iter()
+			.map(|(i, j)| (a[i].clone(), b[j].clone()))
+			.collect(),
+	}
+}
+
+/// Pair as many of the bricks `a[from]` with bricks `b[to]` as possible,
+/// minimizing the total distance between the bricks of each pair.
+fn closest_pairs(a: &[Brick], from: &[usize], b: &[Brick], to: &[usize]) -> Vec<(usize, usize)> {
+	if from.len().max(to.len()) > EXACT_PAIRING_LIMIT {
+		return if from.len() <= to.len() {
+			nearest_pairs(a, from, b, to)
+		} else {
+			nearest_pairs(b, to, a, from)
+				.into_iter()
+				.map(|(j, i)| (i, j))
+				.collect()
+		};
+	}
+
+	// Stands in for infinite distances, which the assignment can't sum.
+	const FAR: f64 = 1e39;
+
+	let cost = |i: usize, j: usize| {
+		let distance = f64::from(a[i].base.distance_to(&b[j].base));
+		if distance.is_finite() {
+			distance
+		} else {
+			FAR
+		}
+	};
+
+	if from.len() <= to.len() {
+		let costs: Vec<f64> = from
+			.iter()
+			.flat_map(|&i| to.iter().map(move |&j| (i, j)))
+			.map(|(i, j)| cost(i, j))
+			.collect();
+		min_cost_assignment(from.len(), to.len(), &costs)
+			.into_iter()
+			.enumerate()
+			.map(|(row, col)| (from[row], to[col]))
+			.collect()
+	} else {
+		let costs: Vec<f64> = to
+			.iter()
+			.flat_map(|&j| from.iter().map(move |&i| (i, j)))
+			.map(|(i, j)| cost(i, j))
+			.collect();
+		min_cost_assignment(to.len(), from.len(), &costs)
+			.into_iter()
+			.enumerate()
+			.map(|(row, col)| (from[col], to[row]))
+			.collect()
+	}
+}
+
+/// Pair each of the bricks `a[from]`, in order, with the closest brick
+/// `b[to]` that is still unpaired. Requires `from.len() <= to.len()`.
+///
+/// The bricks `b[to]` are hashed into cubes of grid positions, growing the
+/// cubes until there are about as many cubes as bricks per cube. Each
+/// search visits the cubes nearest first and stops at the first cube that
+/// can't hold a closer brick.
+fn nearest_pairs(a: &[Brick], from: &[usize], b: &[Brick], to: &[usize]) -> Vec<(usize, usize)> {
+	debug_assert!(from.len() <= to.len());
+
+	let target = (to.len() as f64).sqrt().ceil() as usize;
+	let mut size = 1;
+	let mut cubes = hash_positions(b, to, size);
+	while cubes.len() > target && size < 1 << 32 {
+		size *= 2;
+		cubes = hash_positions(b, to, size);
+	}
+	let mut cubes: Vec<((i64, i64, i64), Vec<usize>)> = cubes.into_iter().collect();
+	cubes.sort_unstable_by_key(|(cube, _)| *cube);
+
+	let mut pairs = Vec::with_capacity(from.len());
+	for &i in from {
+		let brick = &a[i].base;
+		let mut nearest: Vec<(f64, usize)> = cubes
+			.iter()
+			.enumerate()
+			.filter(|(_, (_, members))| !members.is_empty())
+			.map(|(index, (cube, _))| (cube_distance(brick.position, *cube, size), index))
+			.collect();
+		nearest.sort_unstable_by(|x, y| x.0.total_cmp(&y.0));
+
+		let mut best: Option<(f32, usize, usize)> = None;
+		for (bound, index) in nearest {
+			if let Some((distance, ..)) = best {
+				if bound > f64::from(distance) {
+					break;
+				}
+			}
+			for (slot, &j) in cubes[index].1.iter().enumerate() {
+				let distance = brick.distance_to(&b[j].base);
+				let closer = match best {
+					Some((best, ..)) => distance < best,
+					None => true,
+				};
+				if closer {
+					best = Some((distance, index, slot));
+				}
+			}
+		}
+
+		if let Some((_, index, slot)) = best {
+			pairs.push((i, cubes[index].1.swap_remove(slot)));
+		}
+	}
+	pairs
+}
+
+/// Group the bricks `bricks[indices]` by the cube of `size` grid positions
+/// along each axis they lie in.
+fn hash_positions(
+	bricks: &[Brick],
+	indices: &[usize],
+	size: i64,
+) -> HashMap<(i64, i64, i64), Vec<usize>> {
+	let mut cubes: HashMap<_, Vec<usize>> = HashMap::new();
+	for &index in indices {
+		let grid = bricks[index].base.grid_position();
+		let cube = (
+			i64::from(grid.0).div_euclid(size),
+			i64::from(grid.1).div_euclid(size),
+			i64::from(grid.2).div_euclid(size),
+		);
+		cubes.entry(cube).or_default().push(index);
+	}
+	cubes
+}
+
+/// A lower bound on the distance from `position` to any brick in a cube
+/// from [`hash_positions`](fn.hash_positions.html).
+fn cube_distance(position: (f32, f32, f32), cube: (i64, i64, i64), size: i64) -> f64 {
+	// Grid positions are rounded, so the cube reaches half a grid step
+	// beyond its first and last positions.
+	let gap = |position: f32, cube: i64, step: f32| {
+		let step = f64::from(step);
+		let low = (cube * size) as f64 - 0.5;
+		let high = ((cube + 1) * size) as f64 - 0.5;
+		let position = f64::from(position) / step;
+		(low - position).max(position - high).max(0.0) * step
+	};
+	let gaps = (
+		gap(position.0, cube.0, GRID_XY),
+		gap(position.1, cube.1, GRID_XY),
+		gap(position.2, cube.2, GRID_Z),
+	);
+	(gaps.0 * gaps.0 + gaps.1 * gaps.1 + gaps.2 * gaps.2).sqrt()
+}
+
+/// Assign a distinct column to each row of a `rows` by `cols` cost matrix,
+/// stored row by row, so that the total cost is minimal. Requires
+/// `rows <= cols`. Returns the column of each row.
+///
+/// This is the Hungarian algorithm with potentials, taking `O(rows² cols)`
+/// time.
+fn min_cost_assignment(rows: usize, cols: usize, costs: &[f64]) -> Vec<usize> {
+	debug_assert!(rows <= cols);
+
+	// Rows and columns are numbered from 1; column 0 holds the row being
+	// added, and row 0 means unassigned.
+	let mut row_potential = vec![0.0; rows + 1];
+	let mut col_potential = vec![0.0; cols + 1];
+	let mut row_of = vec![0; cols + 1];
+	let mut previous = vec![0; cols + 1];
+
+	for row in 1..=rows {
+		row_of[0] = row;
+		let mut col = 0;
+		let mut slack = vec![f64::INFINITY; cols + 1];
+		let mut visited = vec![false; cols + 1];
+
+		// Grow a tree of alternating paths until it reaches a free column.
+		loop {
+			visited[col] = true;
+			let current = row_of[col];
+			let mut delta = f64::INFINITY;
+			let mut next = 0;
+			for other in 1..=cols {
+				if visited[other] {
+					continue;
+				}
+				let reduced = costs[(current - 1) * cols + other - 1]
+					- row_potential[current]
+					- col_potential[other];
+				if reduced < slack[other] {
+					slack[other] = reduced;
+					previous[other] = col;
+				}
+				if slack[other] < delta {
+					delta = slack[other];
+					next = other;
+				}
+			}
+			for other in 0..=cols {
+				if visited[other] {
+					row_potential[row_of[other]] += delta;
+					col_potential[other] -= delta;
+				} else {
+					slack[other] -= delta;
+				}
+			}
+			col = next;
+			if row_of[col] == 0 {
+				break;
+			}
+		}
+
+		// Flip the path to the free column.
+		while col != 0 {
+			let prior = previous[col];
+			row_of[col] = row_of[prior];
+			col = prior;
+		}
+	}
+
+	let mut assignment = vec![0; rows];
+	for (col, &row) in row_of.iter().enumerate().skip(1) {
+		if row != 0 {
+			assignment[row - 1] = col - 1;
+		}
+	}
+	assignment
+}
+
+fn diff_key(brick: &Brick) -> DiffKey<'_> {
+	(&brick.base.ui_name, brick.base.angle, brick.base.color_index)
+}
+
+fn unpaired(bricks: &[Brick], paired: &[bool]) -> Vec<Brick> {
+	bricks
+		.iter()
+		.zip(paired)
+		.filter(|(_, &paired)| !paired)
+		.map(|(brick, _)| brick.clone())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture::brick;
+
+	#[test]
+	fn finds_added_and_moved_bricks() {
+		let a = vec![brick("1x1", (0.0, 0.0, 0.2)), brick("2x2", (1.0, 0.0, 0.2))];
+		let b = vec![
+			brick("1x1", (0.0, 0.0, 0.2)),
+			brick("2x2", (2.0, 0.0, 0.2)),
+			brick("1x1", (5.0, 0.0, 0.2)),
+			brick("4x4", (0.0, 0.0, 0.6)),
+		];
+
+		let diff = save_diff(&a, &b);
+		assert_eq!(diff.summarize(), "2 added, 0 removed, 1 moved");
+		assert_eq!(diff.moved[0].0.base.position, (1.0, 0.0, 0.2));
+		assert_eq!(diff.moved[0].1.base.position, (2.0, 0.0, 0.2));
+	}
+
+	#[test]
+	fn pairs_minimize_total_distance() {
+		// Pairing the closest bricks first would move 2 to 1.75 and 0 to 4.
+		let a = vec![brick("1x1", (0.0, 0.0, 0.2)), brick("1x1", (2.0, 0.0, 0.2))];
+		let b = vec![brick("1x1", (1.75, 0.0, 0.2)), brick("1x1", (4.0, 0.0, 0.2))];
+
+		let diff = save_diff(&a, &b);
+		let moves: Vec<_> = diff
+			.moved
+			.iter()
+			.map(|(old, new)| (old.base.position.0, new.base.position.0))
+			.collect();
+		assert_eq!(moves, vec![(0.0, 1.75), (2.0, 4.0)]);
+	}
+
+	#[test]
+	fn unequal_groups_leave_the_farthest_unpaired() {
+		let a = vec![
+			brick("1x1", (0.0, 0.0, 0.2)),
+			brick("1x1", (10.0, 0.0, 0.2)),
+			brick("1x1", (20.0, 0.0, 0.2)),
+		];
+		let b = vec![brick("1x1", (9.0, 0.0, 0.2))];
+
+		let diff = save_diff(&a, &b);
+		assert_eq!(diff.summarize(), "0 added, 2 removed, 1 moved");
+		assert_eq!(diff.moved[0].0.base.position.0, 10.0);
+	}
+
+	#[test]
+	fn large_shifted_group_pairs_each_brick_with_its_copy() {
+		let a: Vec<Brick> = (0..10_000)
+			.map(|i| brick("1x1", ((i % 100) as f32 * 0.5, (i / 100) as f32 * 0.5, 0.2)))
+			.collect();
+		let b: Vec<Brick> = a
+			.iter()
+			.map(|old| {
+				let (x, y, z) = old.base.position;
+				brick("1x1", (x, y, z + 10.0))
+			})
+			.collect();
+
+		let diff = save_diff(&a, &b);
+		assert_eq!(diff.summarize(), "0 added, 0 removed, 10000 moved");
+		for (old, new) in &diff.moved {
+			assert_eq!(new.base.position.0, old.base.position.0);
+			assert_eq!(new.base.position.1, old.base.position.1);
+			assert_eq!(new.base.position.2, old.base.position.2 + 10.0);
+		}
+
+		// More bricks than the limit on only one side.
+		let diff = save_diff(&a[..1], &b);
+		assert_eq!(diff.summarize(), "9999 added, 0 removed, 1 moved");
+		assert_eq!(diff.moved[0].1.base.position, (0.0, 0.0, 10.2));
+	}
+
+	#[test]
+	fn identical_sets_have_no_differences() {
+		let bricks = vec![brick("1x1", (0.0, 0.0, 0.2)), brick("1x1", (0.0, 0.0, 0.2))];
+		assert!(save_diff(&bricks, &bricks).is_empty());
+	}
+}
@@ -0,0 +1,135 @@
+use crate::Brick;
+
+const EVENT_KEY: &str = "EVENT";
+
+/// An event on a brick, stored in save files as a tab-separated `+-EVENT`
+/// line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrickEvent {
+	/// The position of the event in the brick's event list.
+	pub event_id: u32,
+	/// Whether the event is enabled.
+	pub enabled: bool,
+	/// The input event that triggers it, such as `onActivate`.
+	pub event_name: String,
+	/// The delay in milliseconds before the output runs.
+	pub delay: u32,
+	/// The target of the output, such as `Self` or `Player`.
+	pub target: String,
+	/// The name of the target brick when `target` refers to a named brick,
+	/// otherwise "".
+	pub named_target: String,
+	/// The output event to run, such as `setColor`.
+	pub output_type: String,
+	/// The parameters of the output event.
+	pub args: Vec<String>,
+}
+
+impl BrickEvent {
+	fn parse(fields: &str) -> Option<Self> {
+		let mut fields = fields.split('\t');
+		Some(Self {
+			event_id: fields.next()?.parse().ok()?,
+			enabled: fields.next()?.parse::<u8>().ok()? != 0,
+			event_name: String::from(fields.next()?),
+			delay: fields.next()?.parse().ok()?,
+			target: String::from(fields.next()?),
+			named_target: String::from(fields.next()?),
+			output_type: String::from(fields.next()?),
+			args: fields.map(String::from).collect(),
+		})
+	}
+}
+
+/// Parse the events of a brick from its `+-EVENT` extra data lines.
+/// Malformed lines are skipped.
+pub fn read_events(brick: &Brick) -> Vec<BrickEvent> {
+	brick
+		.extras()
+		.filter(|(key, _)| key.eq_ignore_ascii_case(EVENT_KEY))
+		.filter_map(|(_, fields)| BrickEvent::parse(fields))
+		.collect()
+}
+
+/// Format events as `+-EVENT` extra data lines, ready to be stored in
+/// `unknown_extra`.
+pub fn write_events(events: &[BrickEvent]) -> Vec<String> {
+	events
+		.iter()
+		.map(|event| {
+			let mut line = format!(
+				"+-{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+				EVENT_KEY,
+				event.event_id,
+				u8::from(event.enabled),
+				event.event_name,
+				event.delay,
+				event.target,
+				event.named_target,
+				event.output_type,
+			);
+			for arg in &event.args {
+				line.push('\t');
+				line.push_str(arg);
+			}
+			line
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	#[test]
+	fn skips_malformed_events() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("1x1", (0.0, 0.0, 0.2)),
+			"+-EVENT\t0\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+			"+-EVENT\tfirst\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+			"+-EVENT\t2\t0\tonRelay\t33\tNamedBrick\t_door\tdoorOpen",
+			"+-NTOBJECTNAME _door",
+		]));
+
+		let events = read_events(&bricks[0]);
+		assert_eq!(
+			events,
+			vec![
+				BrickEvent {
+					event_id: 0,
+					enabled: true,
+					event_name: String::from("onActivate"),
+					delay: 0,
+					target: String::from("Self"),
+					named_target: String::new(),
+					output_type: String::from("setColor"),
+					args: vec![String::from("3")],
+				},
+				BrickEvent {
+					event_id: 2,
+					enabled: false,
+					event_name: String::from("onRelay"),
+					delay: 33,
+					target: String::from("NamedBrick"),
+					named_target: String::from("_door"),
+					output_type: String::from("doorOpen"),
+					args: Vec::new(),
+				},
+			]
+		);
+		assert!(read_events(&fixture::brick("1x1", (0.0, 0.0, 0.2))).is_empty());
+	}
+
+	#[test]
+	fn write_events_round_trip() {
+		let lines = vec![
+			"+-EVENT\t0\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+			"+-EVENT\t1\t0\tonRelay\t33\tNamedBrick\t_door\tdoorOpen",
+		];
+		let mut brick = fixture::brick("1x1", (0.0, 0.0, 0.2));
+		brick.unknown_extra = lines.iter().map(|&line| String::from(line)).collect();
+
+		assert_eq!(write_events(&read_events(&brick)), lines);
+	}
+}
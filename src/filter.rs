@@ -0,0 +1,142 @@
+use crate::{query::in_box, Brick, Reader};
+use std::io::{self, prelude::*};
+use std::ops::Deref;
+
+impl<R: BufRead> Reader<R> {
+	/// Only read bricks positioned within the inclusive box `[min, max]`.
+	///
+	/// Brick lines outside the box are skipped after parsing just their
+	/// position, along with their extra data. This saves work when a small
+	/// region of a large save is needed; for small saves, filtering the
+	/// bricks afterwards with [`query::filter_box`](query/fn.filter_box.html)
+	/// is just as fast.
+	///
+	/// ```rust,no_run
+	/// # use std::{fs::File, io::BufReader};
+	/// # fn main() -> std::io::Result<()> {
+	/// let reader = bl_save::Reader::new(BufReader::new(File::open("House.bls")?))?;
+	///
+	/// for brick in reader.with_position_filter((-8.0, -8.0, 0.0), (8.0, 8.0, 16.0)) {
+	///     println!("{}", brick?);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_position_filter(
+		mut self,
+		min: (f32, f32, f32),
+		max: (f32, f32, f32),
+	) -> FilteredReader<R> {
+		self.set_position_filter(min, max);
+		FilteredReader {
+			reader: self,
+			min,
+			max,
+		}
+	}
+}
+
+/// A reader that skips bricks outside a box, created by
+/// [`Reader::with_position_filter`](struct.Reader.html#method.with_position_filter).
+///
+/// Metadata accessors of the inner [`Reader`](struct.Reader.html) are
+/// available through `Deref`.
+pub struct FilteredReader<R: BufRead> {
+	reader: Reader<R>,
+	min: (f32, f32, f32),
+	max: (f32, f32, f32),
+}
+
+impl<R: BufRead> Deref for FilteredReader<R> {
+	type Target = Reader<R>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.reader
+	}
+}
+
+impl<R: BufRead> Iterator for FilteredReader<R> {
+	type Item = io::Result<Brick>;
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, self.reader.size_hint().1)
+	}
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.reader.next()? {
+				// A brick line read ahead before the filter was set may
+				// still be outside the box.
+				Ok(brick) if !in_box(brick.base.position, self.min, self.max) => continue,
+				result => return Some(result),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{fixture, Brick, Reader};
+	use std::io;
+
+	fn read_filtered(save: &str) -> Vec<Brick> {
+		Reader::from_str(save)
+			.unwrap()
+			.with_position_filter((0.0, 0.0, 0.0), (10.0, 10.0, 10.0))
+			.collect::<io::Result<_>>()
+			.unwrap()
+	}
+
+	#[test]
+	fn skipped_bricks_drop_their_extras() {
+		let save = fixture::save(&[
+			&fixture::line("Outside", (-5.0, 0.0, 0.2)),
+			"+-OWNER 1",
+			"+-EVENT\t0\t1\tonActivate\t0\tSelf\t\tsetColor\t3",
+			&fixture::line("Inside", (5.0, 5.0, 0.2)),
+			"+-OWNER 2",
+			&fixture::line("Far", (50.0, 0.0, 0.2)),
+			"+-OWNER 3",
+			"+-NTOBJECTNAME _far",
+			&fixture::line("Also inside", (1.0, 1.0, 1.0)),
+		]);
+
+		let bricks = read_filtered(&save);
+		assert_eq!(bricks.len(), 2);
+		assert_eq!(bricks[0].base.ui_name, "Inside");
+		assert_eq!(bricks[0].owner(), Some("2"));
+		assert!(bricks[0].unknown_extra.is_empty());
+		assert_eq!(bricks[1].base.ui_name, "Also inside");
+		assert_eq!(bricks[1].owner(), None);
+		assert!(bricks[1].unknown_extra.is_empty());
+	}
+
+	#[test]
+	fn brick_read_before_filter_is_checked() {
+		let save = fixture::save(&[
+			&fixture::line("Outside", (-5.0, 0.0, 0.2)),
+			&fixture::line("Inside", (5.0, 5.0, 0.2)),
+		])
+		.replace("Linecount 2\r\n", "");
+
+		let bricks = read_filtered(&save);
+		assert_eq!(bricks.len(), 1);
+		assert_eq!(bricks[0].base.ui_name, "Inside");
+	}
+
+	#[test]
+	fn malformed_lines_are_not_skipped() {
+		let save = fixture::save(&[
+			&fixture::line("Outside", (-5.0, 0.0, 0.2)),
+			"No quote",
+			&fixture::line("Inside", (5.0, 5.0, 0.2)),
+		]);
+
+		let mut reader = Reader::from_str(&save)
+			.unwrap()
+			.with_position_filter((0.0, 0.0, 0.0), (10.0, 10.0, 10.0));
+		assert!(reader.next().unwrap().is_err());
+		assert_eq!(reader.next().unwrap().unwrap().base.ui_name, "Inside");
+		assert!(reader.next().is_none());
+	}
+}
@@ -0,0 +1,92 @@
+//! Save files and bricks for tests.
+
+use crate::{Brick, BrickBase, Reader};
+
+pub const HEADER: &str =
+	"This is a Blockland save file.  You probably shouldn't modify it cause you'll screw it up.";
+
+/// A save with a one-line description, a gray colorset, a `Linecount` line
+/// and then `lines`, all ending in `\r\n`.
+///
+/// The first line after the `Linecount` line is line 69.
+pub fn save(lines: &[&str]) -> String {
+	save_with_description(&["Test"], lines)
+}
+
+/// Like [`save`](fn.save.html), but with the given description lines.
+pub fn save_with_description(description: &[&str], lines: &[&str]) -> String {
+	let mut save = format!("{}\r\n{}\r\n", HEADER, description.len());
+	for line in description {
+		save.push_str(line);
+		save.push_str("\r\n");
+	}
+	for _ in 0..64 {
+		save.push_str("0.5 0.5 0.5 1\r\n");
+	}
+	let brick_count = lines.iter().filter(|line| !line.starts_with("+-")).count();
+	save.push_str(&format!("Linecount {}\r\n", brick_count));
+	for line in lines {
+		save.push_str(line);
+		save.push_str("\r\n");
+	}
+	save
+}
+
+/// A palette with red, yellow, green, blue, white and black in slots 0 to
+/// 5, translucent red in slot 6, an invisible slot 7 and opaque gray in
+/// the rest.
+pub fn palette() -> String {
+	let mut palette = String::from(
+		"0.898039 0.000000 0.000000 1.000000\r\n\
+		 0.898039 0.898039 0.000000 1.000000\r\n\
+		 0.000000 0.498039 0.247059 1.000000\r\n\
+		 0.200000 0.000000 0.800000 1.000000\r\n\
+		 1.000000 1.000000 1.000000 1.000000\r\n\
+		 0.000000 0.000000 0.000000 1.000000\r\n\
+		 0.898039 0.000000 0.000000 0.498039\r\n\
+		 1.000000 1.000000 1.000000 0.000000\r\n",
+	);
+	for _ in 8..64 {
+		palette.push_str("0.500000 0.500000 0.500000 1.000000\r\n");
+	}
+	palette
+}
+
+/// A save with `count` bricks, where brick `i` is named `i` and positioned
+/// at `(i, 0, 0.2)`.
+pub fn numbered(count: usize) -> String {
+	let lines: Vec<String> = (0..count)
+		.map(|i| line(&i.to_string(), (i as f32, 0.0, 0.2)))
+		.collect();
+	let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+	save(&lines)
+}
+
+/// Read every brick of a save, panicking on errors.
+pub fn read(save: &str) -> Vec<Brick> {
+	Reader::from_str(save)
+		.unwrap()
+		.map(|brick| brick.unwrap())
+		.collect()
+}
+
+/// A brick line for an unrotated, rendered brick using color `0`.
+pub fn line(ui_name: &str, position: (f32, f32, f32)) -> String {
+	format!(
+		"{}\" {} {} {} 0 0 0  0 0 1 1 1",
+		ui_name, position.0, position.1, position.2
+	)
+}
+
+/// A brick with default fields and no extra data.
+pub fn brick(ui_name: &str, position: (f32, f32, f32)) -> Brick {
+	Brick {
+		base: BrickBase {
+			ui_name: String::from(ui_name),
+			position,
+			..BrickBase::with_defaults()
+		},
+		owner: None,
+		unknown_extra: Vec::new(),
+	}
+}
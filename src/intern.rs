@@ -0,0 +1,104 @@
+use crate::{Brick, BrickBase};
+use std::{cell::RefCell, collections::HashSet, sync::Arc};
+
+thread_local! {
+	static STRINGS: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+/// Look up a shared copy of `s` in the intern table of the current thread,
+/// adding it if missing.
+fn intern(s: String) -> Arc<str> {
+	STRINGS.with(|strings| {
+		let mut strings = strings.borrow_mut();
+		match strings.get(s.as_str()) {
+			Some(shared) => Arc::clone(shared),
+			None => {
+				let shared: Arc<str> = Arc::from(s);
+				strings.insert(Arc::clone(&shared));
+				shared
+			}
+		}
+	})
+}
+
+impl BrickBase {
+	/// Convert the strings of the brick to shared `Arc<str>`s.
+	///
+	/// Strings are interned in a table local to the current thread, so
+	/// bricks converted on the same thread with equal `ui_name`s or prints
+	/// share one allocation. The table is never cleared; it holds one copy
+	/// of each distinct string converted on the thread.
+	pub fn into_arc(self) -> BrickBase<Arc<str>> {
+		BrickBase {
+			ui_name: intern(self.ui_name),
+			position: self.position,
+			angle: self.angle,
+			is_baseplate: self.is_baseplate,
+			color_index: self.color_index,
+			print: intern(self.print),
+			color_fx: self.color_fx,
+			shape_fx: self.shape_fx,
+			raycasting: self.raycasting,
+			collision: self.collision,
+			rendering: self.rendering,
+		}
+	}
+}
+
+impl From<BrickBase> for BrickBase<Arc<str>> {
+	fn from(base: BrickBase) -> Self {
+		base.into_arc()
+	}
+}
+
+impl Brick {
+	/// Convert the strings of the brick, including its owner and extra
+	/// data, to `Arc<str>`s.
+	///
+	/// Only the base is interned, as described in
+	/// [`BrickBase::into_arc`](struct.BrickBase.html#method.into_arc). Owners
+	/// and extra data lines such as events are mostly unique, so each gets
+	/// its own allocation and is freed along with the brick.
+	pub fn into_arc(self) -> Brick<Arc<str>> {
+		Brick {
+			base: self.base.into_arc(),
+			owner: self.owner.map(Arc::from),
+			unknown_extra: self.unknown_extra.into_iter().map(Arc::from).collect(),
+		}
+	}
+}
+
+impl From<Brick> for Brick<Arc<str>> {
+	fn from(brick: Brick) -> Self {
+		brick.into_arc()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::fixture::brick;
+	use std::sync::Arc;
+
+	#[test]
+	fn equal_names_share_an_allocation() {
+		let a = brick("1x1 Flat", (0.0, 0.0, 0.0)).into_arc();
+		let b = brick("1x1 Flat", (1.0, 0.0, 0.0)).into_arc();
+		let c = brick("2x2 Flat", (2.0, 0.0, 0.0)).into_arc();
+		assert!(Arc::ptr_eq(&a.base.ui_name, &b.base.ui_name));
+		assert!(Arc::ptr_eq(&a.base.print, &b.base.print));
+		assert!(!Arc::ptr_eq(&a.base.ui_name, &c.base.ui_name));
+	}
+
+	#[test]
+	fn extra_data_is_not_interned() {
+		let mut a = brick("1x1", (0.0, 0.0, 0.0)).set_owner("5");
+		a.unknown_extra.push(String::from("+-NTOBJECTNAME _door"));
+		let b = a.clone();
+		let (a, b) = (a.into_arc(), b.into_arc());
+
+		assert_eq!(a.owner, b.owner);
+		assert!(!Arc::ptr_eq(a.owner.as_ref().unwrap(), b.owner.as_ref().unwrap()));
+		assert_eq!(&*a.unknown_extra[0], "+-NTOBJECTNAME _door");
+		assert!(!Arc::ptr_eq(&a.unknown_extra[0], &b.unknown_extra[0]));
+	}
+}
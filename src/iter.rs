@@ -0,0 +1,79 @@
+use crate::{Brick, Reader};
+use std::io::{self, prelude::*};
+use std::iter::FusedIterator;
+
+impl<R: BufRead> Reader<R> {
+	/// Convert the reader into an iterator over its remaining bricks,
+	/// dropping the metadata.
+	///
+	/// `Reader` is itself an iterator; this gives it a separate, nameable
+	/// type for use in signatures, which also guarantees to keep returning
+	/// `None` once the bricks run out.
+	pub fn into_bricks(self) -> BrickIter<R> {
+		BrickIter {
+			reader: self,
+			finished: false,
+		}
+	}
+}
+
+/// An iterator over the bricks of a save file, created by
+/// [`Reader::into_bricks`](struct.Reader.html#method.into_bricks).
+///
+/// This doesn't implement `ExactSizeIterator`, as the brick count claimed
+/// by a file may be missing or wrong.
+pub struct BrickIter<R: BufRead> {
+	reader: Reader<R>,
+	finished: bool,
+}
+
+impl<R: BufRead> Iterator for BrickIter<R> {
+	type Item = io::Result<Brick>;
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		if self.finished {
+			(0, Some(0))
+		} else {
+			self.reader.size_hint()
+		}
+	}
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.finished {
+			return None;
+		}
+		let item = self.reader.next();
+		self.finished = item.is_none();
+		item
+	}
+}
+
+impl<R: BufRead> FusedIterator for BrickIter<R> {}
+
+#[cfg(test)]
+mod tests {
+	use crate::{fixture, Reader};
+
+	#[test]
+	fn stays_exhausted() {
+		let save = fixture::numbered(2);
+		let mut bricks = Reader::from_str(&save).unwrap().into_bricks();
+		assert!(bricks.next().unwrap().is_ok());
+		assert!(bricks.next().unwrap().is_ok());
+		for _ in 0..3 {
+			assert!(bricks.next().is_none());
+		}
+		assert_eq!(bricks.size_hint(), (0, Some(0)));
+	}
+
+	#[test]
+	fn for_loop_reads_every_brick() {
+		let save = fixture::numbered(3);
+		let mut count = 0;
+		for brick in Reader::from_str(&save).unwrap() {
+			brick.unwrap();
+			count += 1;
+		}
+		assert_eq!(count, 3);
+	}
+}
@@ -5,23 +5,55 @@
 //! [`BufRead`](https://doc.rust-lang.org/std/io/trait.BufRead.html) source to
 //! read the save metadata and iterate over its bricks.
 //!
-//! ```rust
+//! ```rust,no_run
+//! # use std::{fs::File, io::BufReader};
+//! # fn main() -> std::io::Result<()> {
 //! let file = BufReader::new(File::open("House.bls")?);
 //! let reader = bl_save::Reader::new(file)?;
 //!
 //! println!("Description: {}", reader.description());
-//! println!("Brick count: {}", reader.brick_count());
+//! println!("Brick count: {:?}", reader.brick_count());
 //! assert_eq!(reader.colors().len(), 64);
 //!
 //! for brick in reader {
 //!     let brick = brick?;
 //! }
+//! # Ok(())
+//! # }
 //! ```
 
+mod binary;
+pub mod colorset;
+pub mod compat;
 mod cp1252;
+pub mod csv;
 mod data;
+mod diff;
 mod escape;
+mod events;
+mod filter;
+#[cfg(test)]
+mod fixture;
+mod intern;
+mod iter;
+mod progress;
+pub mod query;
 mod read;
+mod take;
+pub mod transform;
+mod validate;
 
-pub use data::{Brick, BrickBase};
-pub use read::Reader;
+pub use data::{Brick, BrickBase, BrickFlags, Color, Colors, POSITION_EPSILON};
+pub use diff::{save_diff, SaveDiff};
+pub use events::{read_events, write_events, BrickEvent};
+pub use filter::FilteredReader;
+pub use iter::BrickIter;
+pub use progress::{with_progress, ProgressReader};
+pub use read::{
+	is_valid_header, probe, read_brick_count_only, read_description_only, LimitExceeded,
+	ProbeResult, ReadLimits, Reader, ReaderBuilder,
+};
+pub use take::TakeBricks;
+pub use validate::{
+	validate_bls_file, verify_linecount, LinecountResult, ValidationSummary, Warning,
+};
@@ -0,0 +1,131 @@
+use crate::{Brick, Reader};
+use std::io::{self, prelude::*};
+use std::ops::Deref;
+
+/// Wrap a reader to report progress while iterating over its bricks.
+///
+/// Besides the number of bricks yielded so far, `callback` receives a
+/// percentage estimate when the brick count is known, hence its second
+/// argument. See [`ProgressReader`](struct.ProgressReader.html).
+pub fn with_progress<R: BufRead, F: FnMut(usize, Option<f32>)>(
+	reader: Reader<R>,
+	callback: F,
+) -> ProgressReader<R, F> {
+	ProgressReader {
+		reader,
+		callback,
+		report_every: 1,
+		bricks_yielded: 0,
+		finished: false,
+	}
+}
+
+/// A reader that calls a callback as bricks are read, for updating
+/// progress bars and the like.
+///
+/// The callback receives the number of bricks yielded so far and, if the
+/// brick count is known, an estimated percentage of completion. It is
+/// called after every Nth brick (every brick by default) and once more
+/// when the reader is exhausted.
+///
+/// Metadata accessors of the inner [`Reader`](struct.Reader.html) are
+/// available through `Deref`.
+pub struct ProgressReader<R: BufRead, F> {
+	reader: Reader<R>,
+	callback: F,
+	report_every: usize,
+	bricks_yielded: usize,
+	finished: bool,
+}
+
+impl<R: BufRead, F: FnMut(usize, Option<f32>)> ProgressReader<R, F> {
+	/// Call the callback after every `n` bricks instead of every brick.
+	/// `0` is treated as `1`.
+	pub fn report_every(mut self, n: usize) -> Self {
+		self.report_every = n.max(1);
+		self
+	}
+
+	/// Unwrap the inner reader.
+	pub fn into_inner(self) -> Reader<R> {
+		self.reader
+	}
+
+	fn report(&mut self) {
+		let percentage = self.reader.brick_count().map(|count| {
+			if count == 0 {
+				100.0
+			} else {
+				(self.bricks_yielded as f32 * 100.0 / count as f32).min(100.0)
+			}
+		});
+		(self.callback)(self.bricks_yielded, percentage);
+	}
+}
+
+impl<R: BufRead, F> Deref for ProgressReader<R, F> {
+	type Target = Reader<R>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.reader
+	}
+}
+
+impl<R: BufRead, F: FnMut(usize, Option<f32>)> Iterator for ProgressReader<R, F> {
+	type Item = io::Result<Brick>;
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.reader.size_hint()
+	}
+
+	// `usize::is_multiple_of` needs Rust 1.87.
+	#[allow(clippy::manual_is_multiple_of)]
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.reader.next() {
+			Some(Ok(brick)) => {
+				self.bricks_yielded += 1;
+				if self.bricks_yielded % self.report_every == 0 {
+					self.report();
+				}
+				Some(Ok(brick))
+			}
+			Some(Err(e)) => Some(Err(e)),
+			None => {
+				if !self.finished {
+					self.finished = true;
+					self.report();
+				}
+				None
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	#[test]
+	fn reports_every_nth_brick_and_at_the_end() {
+		let save = fixture::numbered(10);
+
+		let mut calls = Vec::new();
+		let count = with_progress(Reader::from_str(&save).unwrap(), |bricks, percentage| {
+			calls.push((bricks, percentage))
+		})
+		.report_every(3)
+		.count();
+
+		assert_eq!(count, 10);
+		assert_eq!(
+			calls,
+			vec![
+				(3, Some(30.0)),
+				(6, Some(60.0)),
+				(9, Some(90.0)),
+				(10, Some(100.0)),
+			]
+		);
+	}
+}
@@ -0,0 +1,552 @@
+//! Queries over collections of bricks.
+
+use crate::Brick;
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+/// The default cell size of a [`BrickIndex`](struct.BrickIndex.html),
+/// in world units (16 studs).
+pub const DEFAULT_CELL_SIZE: f32 = 8.0;
+
+type Cell = (i32, i32, i32);
+
+/// Group bricks by their `ui_name`.
+pub fn group_by_type(bricks: &[Brick]) -> HashMap<&str, Vec<&Brick>> {
+	let mut groups: HashMap<&str, Vec<&Brick>> = HashMap::new();
+	for brick in bricks {
+		groups.entry(&brick.base.ui_name).or_default().push(brick);
+	}
+	groups
+}
+
+/// Count bricks by their `ui_name`.
+pub fn count_by_type(bricks: &[Brick]) -> HashMap<&str, usize> {
+	let mut counts = HashMap::new();
+	for brick in bricks {
+		*counts.entry(brick.base.ui_name.as_str()).or_insert(0) += 1;
+	}
+	counts
+}
+
+/// Count bricks by their color index. Bricks with an out-of-range color
+/// index are not counted.
+pub fn count_by_color(bricks: &[Brick]) -> [usize; 64] {
+	let mut counts = [0; 64];
+	for brick in bricks {
+		if let Some(count) = counts.get_mut(usize::from(brick.base.color_index)) {
+			*count += 1;
+		}
+	}
+	counts
+}
+
+/// The most used color index, preferring the lowest index on ties.
+/// `None` if no brick has a valid color index.
+pub fn dominant_color(bricks: &[Brick]) -> Option<u8> {
+	let counts = count_by_color(bricks);
+	let (index, &count) = counts
+		.iter()
+		.enumerate()
+		.rev()
+		.max_by_key(|(_, &count)| count)?;
+	if count == 0 {
+		None
+	} else {
+		Some(index as u8)
+	}
+}
+
+/// The color indices no brick uses, in ascending order.
+pub fn unused_color_slots(bricks: &[Brick]) -> Vec<u8> {
+	count_by_color(bricks)
+		.iter()
+		.enumerate()
+		.filter(|(_, &count)| count == 0)
+		.map(|(index, _)| index as u8)
+		.collect()
+}
+
+/// Lazily filter bricks with the given `ui_name`.
+pub fn bricks_named<'a>(bricks: &'a [Brick], name: &'a str) -> impl Iterator<Item = &'a Brick> {
+	bricks.iter().filter(move |brick| brick.base.ui_name == name)
+}
+
+/// Collect the distinct print names used by bricks, excluding "".
+///
+/// Names are compared exactly, so differently cased names of the same
+/// print are all included.
+pub fn extract_prints(bricks: &[Brick]) -> HashSet<&str> {
+	bricks
+		.iter()
+		.map(|brick| brick.base.print.as_str())
+		.filter(|print| !print.is_empty())
+		.collect()
+}
+
+/// Lazily filter bricks using the given print, ignoring ASCII case like
+/// Blockland does.
+pub fn bricks_with_print<'a>(bricks: &'a [Brick], print: &'a str) -> impl Iterator<Item = &'a Brick> {
+	bricks
+		.iter()
+		.filter(move |brick| brick.base.print.eq_ignore_ascii_case(print))
+}
+
+/// Lazily filter bricks that use any print.
+pub fn bricks_with_prints(bricks: &[Brick]) -> impl Iterator<Item = &Brick> {
+	bricks.iter().filter(|brick| brick.base.print_is_present())
+}
+
+/// Lazily filter bricks that don't use a print.
+pub fn bricks_without_prints(bricks: &[Brick]) -> impl Iterator<Item = &Brick> {
+	bricks.iter().filter(|brick| !brick.base.print_is_present())
+}
+
+/// Lazily filter bricks with the given owner, ignoring ASCII case.
+// `Option::is_some_and` needs Rust 1.70.
+#[allow(clippy::unnecessary_map_or)]
+pub fn owned_by<'a>(bricks: &'a [Brick], owner: &'a str) -> impl Iterator<Item = &'a Brick> {
+	bricks.iter().filter(move |brick| {
+		brick
+			.owner()
+			.map_or(false, |other| other.eq_ignore_ascii_case(owner))
+	})
+}
+
+/// Find bricks of the same type placed at the same spot, regardless of
+/// rotation. Positions are snapped to the brick grid before comparison.
+///
+/// Returns `(first, overlapping)` index pairs, where `first` is the index of
+/// the first brick at that spot and `overlapping` is the index of a later
+/// one. See also [`transform::find_duplicates`](../transform/fn.find_duplicates.html),
+/// which also compares angles.
+pub fn find_overlapping(bricks: &[Brick]) -> Vec<(usize, usize)> {
+	let mut first_seen: HashMap<(&str, Cell), usize> = HashMap::new();
+	let mut overlapping = Vec::new();
+
+	for (index, brick) in bricks.iter().enumerate() {
+		let key = (brick.base.ui_name.as_str(), brick.base.grid_position());
+		let first = *first_seen.entry(key).or_insert(index);
+		if first != index {
+			overlapping.push((first, index));
+		}
+	}
+
+	overlapping
+}
+
+/// Find all bricks positioned within the inclusive box `[min, max]`.
+pub fn find_in_box(
+	bricks: &[Brick],
+	min: (f32, f32, f32),
+	max: (f32, f32, f32),
+) -> Vec<&Brick> {
+	bricks
+		.iter()
+		.filter(|brick| in_box(brick.base.position, min, max))
+		.collect()
+}
+
+/// Lazily filter bricks positioned within the inclusive box `[min, max]`.
+pub fn filter_box(
+	bricks: impl Iterator<Item = Brick>,
+	min: (f32, f32, f32),
+	max: (f32, f32, f32),
+) -> impl Iterator<Item = Brick> {
+	bricks.filter(move |brick| in_box(brick.base.position, min, max))
+}
+
+pub(crate) fn in_box(position: (f32, f32, f32), min: (f32, f32, f32), max: (f32, f32, f32)) -> bool {
+	position.0 >= min.0
+		&& position.0 <= max.0
+		&& position.1 >= min.1
+		&& position.1 <= max.1
+		&& position.2 >= min.2
+		&& position.2 <= max.2
+}
+
+/// A spatial index over a slice of bricks for repeated box queries.
+///
+/// Bricks are bucketed into a uniform grid of cubic cells by position,
+/// so a query only visits the cells overlapping the box.
+pub struct BrickIndex<'a> {
+	bricks: &'a [Brick],
+	cell_size: f32,
+	cells: HashMap<Cell, Vec<usize>>,
+}
+
+impl<'a> BrickIndex<'a> {
+	/// Build an index using [`DEFAULT_CELL_SIZE`](constant.DEFAULT_CELL_SIZE.html).
+	pub fn new(bricks: &'a [Brick]) -> Self {
+		Self::with_cell_size(bricks, DEFAULT_CELL_SIZE)
+	}
+
+	/// Build an index with cells of the given size in world units.
+	///
+	/// # Panics
+	///
+	/// Panics if `cell_size` is not a positive, finite number.
+	pub fn with_cell_size(bricks: &'a [Brick], cell_size: f32) -> Self {
+		assert!(
+			cell_size > 0.0 && cell_size.is_finite(),
+			"cell size must be positive and finite"
+		);
+
+		let mut cells: HashMap<Cell, Vec<usize>> = HashMap::new();
+		for (index, brick) in bricks.iter().enumerate() {
+			let cell = cell_of(brick.base.position, cell_size);
+			cells.entry(cell).or_default().push(index);
+		}
+
+		Self {
+			bricks,
+			cell_size,
+			cells,
+		}
+	}
+
+	/// Find all indexed bricks positioned within the inclusive box
+	/// `[min, max]`, in the order they appear in the indexed slice.
+	pub fn find_in_box(&self, min: (f32, f32, f32), max: (f32, f32, f32)) -> Vec<&'a Brick> {
+		let lo = cell_of(min, self.cell_size);
+		let hi = cell_of(max, self.cell_size);
+		if lo.0 > hi.0 || lo.1 > hi.1 || lo.2 > hi.2 {
+			return Vec::new();
+		}
+
+		let span = |lo: i32, hi: i32| (i64::from(hi) - i64::from(lo) + 1) as u64;
+		let cell_count = span(lo.0, hi.0)
+			.saturating_mul(span(lo.1, hi.1))
+			.saturating_mul(span(lo.2, hi.2));
+
+		let mut indices: Vec<usize> = Vec::new();
+		if cell_count > self.cells.len() as u64 {
+			// Fewer occupied cells than cells in the box; visit those instead.
+			for (cell, bucket) in &self.cells {
+				if lo.0 <= cell.0
+					&& cell.0 <= hi.0
+					&& lo.1 <= cell.1
+					&& cell.1 <= hi.1
+					&& lo.2 <= cell.2
+					&& cell.2 <= hi.2
+				{
+					indices.extend(bucket);
+				}
+			}
+		} else {
+			for x in lo.0..=hi.0 {
+				for y in lo.1..=hi.1 {
+					for z in lo.2..=hi.2 {
+						if let Some(bucket) = self.cells.get(&(x, y, z)) {
+							indices.extend(bucket);
+						}
+					}
+				}
+			}
+		}
+
+		indices.sort_unstable();
+		indices
+			.into_iter()
+			.map(|index| &self.bricks[index])
+			.filter(|brick| in_box(brick.base.position, min, max))
+			.collect()
+	}
+}
+
+fn cell_of(position: (f32, f32, f32), cell_size: f32) -> Cell {
+	(
+		(position.0 / cell_size).floor() as i32,
+		(position.1 / cell_size).floor() as i32,
+		(position.2 / cell_size).floor() as i32,
+	)
+}
+
+/// A set of conditions that bricks must all satisfy, checked in a single
+/// pass.
+///
+/// A filter with no conditions matches every brick.
+#[derive(Debug, Clone, Default)]
+pub struct BrickFilter {
+	conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+	UiName(String),
+	ColorIndex(u8),
+	Rendering(bool),
+	Collision(bool),
+	IsBaseplate(bool),
+	WithinBox((f32, f32, f32), (f32, f32, f32)),
+}
+
+impl BrickFilter {
+	/// Construct a filter with no conditions.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Require the given `ui_name`.
+	pub fn ui_name(mut self, name: &str) -> Self {
+		self.conditions.push(Condition::UiName(String::from(name)));
+		self
+	}
+
+	/// Require the given color index.
+	pub fn color_index(mut self, index: u8) -> Self {
+		self.conditions.push(Condition::ColorIndex(index));
+		self
+	}
+
+	/// Require the given `rendering` flag.
+	pub fn rendering(mut self, rendering: bool) -> Self {
+		self.conditions.push(Condition::Rendering(rendering));
+		self
+	}
+
+	/// Require the given `collision` flag.
+	pub fn collision(mut self, collision: bool) -> Self {
+		self.conditions.push(Condition::Collision(collision));
+		self
+	}
+
+	/// Require the given `is_baseplate` flag.
+	pub fn is_baseplate(mut self, is_baseplate: bool) -> Self {
+		self.conditions.push(Condition::IsBaseplate(is_baseplate));
+		self
+	}
+
+	/// Require a position within the inclusive box `[min, max]`.
+	pub fn within_box(mut self, min: (f32, f32, f32), max: (f32, f32, f32)) -> Self {
+		self.conditions.push(Condition::WithinBox(min, max));
+		self
+	}
+
+	/// Whether the brick satisfies every condition.
+	pub fn matches(&self, brick: &Brick) -> bool {
+		let base = &brick.base;
+		self.conditions.iter().all(|condition| match condition {
+			Condition::UiName(name) => base.ui_name == *name,
+			Condition::ColorIndex(index) => base.color_index == *index,
+			Condition::Rendering(rendering) => base.rendering == *rendering,
+			Condition::Collision(collision) => base.collision == *collision,
+			Condition::IsBaseplate(is_baseplate) => base.is_baseplate == *is_baseplate,
+			Condition::WithinBox(min, max) => in_box(base.position, *min, *max),
+		})
+	}
+
+	/// Lazily filter bricks, such as those yielded by a
+	/// [`Reader`](../struct.Reader.html). Errors are passed through.
+	pub fn apply(
+		self,
+		bricks: impl Iterator<Item = io::Result<Brick>>,
+	) -> impl Iterator<Item = io::Result<Brick>> {
+		bricks.filter(move |brick| match brick {
+			Ok(brick) => self.matches(brick),
+			Err(_) => true,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture::{self, brick};
+
+	fn mixed() -> Vec<Brick> {
+		fixture::read(&fixture::save(&[
+			&fixture::line("1x1", (0.0, 0.0, 0.2)),
+			&fixture::line("2x2 Plate", (1.0, 0.0, 0.2)),
+			&fixture::line("1x1", (2.0, 0.0, 0.2)),
+			&fixture::line("32x32 Base", (0.0, 0.0, 0.1)),
+			&fixture::line("1x1", (3.0, 0.0, 0.2)),
+		]))
+	}
+
+	#[test]
+	fn counts_and_groups_by_type() {
+		let bricks = mixed();
+
+		let counts = count_by_type(&bricks);
+		assert_eq!(counts.len(), 3);
+		assert_eq!(counts["1x1"], 3);
+		assert_eq!(counts["2x2 Plate"], 1);
+		assert_eq!(counts["32x32 Base"], 1);
+
+		let groups = group_by_type(&bricks);
+		let xs: Vec<f32> = groups["1x1"].iter().map(|b| b.base.position.0).collect();
+		assert_eq!(xs, vec![0.0, 2.0, 3.0]);
+
+		assert_eq!(bricks_named(&bricks, "2x2 Plate").count(), 1);
+		assert_eq!(bricks_named(&bricks, "2x2 plate").count(), 0);
+	}
+
+	#[test]
+	fn box_queries_include_boundaries() {
+		let bricks = vec![
+			brick("Min corner", (0.0, 0.0, 0.0)),
+			brick("Max corner", (8.0, 8.0, 8.0)),
+			brick("Face", (4.0, 8.0, 2.0)),
+			brick("Just outside", (8.25, 4.0, 4.0)),
+			brick("Below", (4.0, 4.0, -0.1)),
+		];
+		let (min, max) = ((0.0, 0.0, 0.0), (8.0, 8.0, 8.0));
+		let expected = vec!["Min corner", "Max corner", "Face"];
+
+		let names = |found: Vec<&Brick>| -> Vec<String> {
+			found.iter().map(|b| b.base.ui_name.clone()).collect()
+		};
+		assert_eq!(names(find_in_box(&bricks, min, max)), expected);
+		for &cell_size in &[0.5, 1.0, 8.0, 100.0] {
+			let index = BrickIndex::with_cell_size(&bricks, cell_size);
+			assert_eq!(names(index.find_in_box(min, max)), expected, "cell size {}", cell_size);
+		}
+
+		let lazy: Vec<String> = filter_box(bricks.into_iter(), min, max)
+			.map(|b| b.base.ui_name)
+			.collect();
+		assert_eq!(lazy, expected);
+	}
+
+	#[test]
+	fn index_handles_empty_and_huge_boxes() {
+		let bricks = vec![brick("A", (1.0, 1.0, 1.0)), brick("B", (-50.0, 20.0, 3.0))];
+		let index = BrickIndex::new(&bricks);
+		assert!(index.find_in_box((5.0, 5.0, 5.0), (0.0, 0.0, 0.0)).is_empty());
+		assert_eq!(index.find_in_box((-1e30, -1e30, -1e30), (1e30, 1e30, 1e30)).len(), 2);
+	}
+
+	#[test]
+	fn empty_filter_matches_everything() {
+		let bricks = mixed();
+		let filtered: Vec<_> = BrickFilter::new()
+			.apply(bricks.into_iter().map(Ok))
+			.collect();
+		assert_eq!(filtered.len(), 5);
+	}
+
+	#[test]
+	fn contradictory_filter_matches_nothing() {
+		let filter = BrickFilter::new().rendering(true).rendering(false);
+		assert_eq!(filter.apply(mixed().into_iter().map(Ok)).count(), 0);
+
+		let filter = BrickFilter::new().ui_name("1x1").ui_name("2x2 Plate");
+		assert_eq!(filter.apply(mixed().into_iter().map(Ok)).count(), 0);
+	}
+
+	#[test]
+	fn filter_combines_conditions_and_keeps_errors() {
+		let mut bricks = mixed();
+		bricks[2].base.collision = false;
+		let input = bricks
+			.into_iter()
+			.map(Ok)
+			.chain(Some(Err(io::Error::new(io::ErrorKind::InvalidData, "bad"))));
+
+		let filter = BrickFilter::new()
+			.ui_name("1x1")
+			.collision(true)
+			.is_baseplate(false)
+			.color_index(0)
+			.within_box((0.0, 0.0, 0.0), (2.5, 1.0, 1.0));
+		let results: Vec<_> = filter.apply(input).collect();
+		assert_eq!(results.len(), 2);
+		assert_eq!(results[0].as_ref().unwrap().base.position.0, 0.0);
+		assert!(results[1].is_err());
+	}
+
+	#[test]
+	fn extract_prints_skips_empty_names() {
+		let bricks = fixture::read(&fixture::save(&[
+			"1x2F Print\" 0 0 0.2 0 0 0 Letters/A 0 0 1 1 1",
+			"1x2F Print\" 1 0 0.2 0 0 0 Letters/A 0 0 1 1 1",
+			&fixture::line("1x2F Print", (2.0, 0.0, 0.2)),
+		]));
+
+		let prints = extract_prints(&bricks);
+		assert_eq!(prints.len(), 1);
+		assert!(prints.contains("Letters/A"));
+
+		assert_eq!(bricks_with_print(&bricks, "letters/a").count(), 2);
+		assert_eq!(bricks_with_prints(&bricks).count(), 2);
+		assert_eq!(bricks_without_prints(&bricks).count(), 1);
+	}
+
+	#[test]
+	fn finds_each_duplicate_once() {
+		let mut lines: Vec<String> = (0..90)
+			.map(|i| fixture::line(&i.to_string(), (i as f32, 0.0, 0.2)))
+			.collect();
+		for i in (0..90).step_by(9) {
+			lines.push(fixture::line(&i.to_string(), (i as f32 + 0.01, 0.0, 0.2)));
+		}
+		let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+		let bricks = fixture::read(&fixture::save(&lines));
+		assert_eq!(bricks.len(), 100);
+
+		let pairs = find_overlapping(&bricks);
+		let expected: Vec<(usize, usize)> = (0..10).map(|n| (n * 9, 90 + n)).collect();
+		assert_eq!(pairs, expected);
+	}
+
+	#[test]
+	fn different_types_do_not_overlap() {
+		let bricks = vec![brick("1x1", (0.0, 0.0, 0.2)), brick("2x2", (0.0, 0.0, 0.2))];
+		assert!(find_overlapping(&bricks).is_empty());
+	}
+
+	#[test]
+	fn counts_colors() {
+		let mut lines = Vec::new();
+		for i in 0..15 {
+			let color = if i % 3 == 0 { 7 } else { 3 };
+			lines.push(format!("1x1\" {} 0 0.2 0 0 {}  0 0 1 1 1", i, color));
+		}
+		let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+		let bricks = fixture::read(&fixture::save(&lines));
+
+		let counts = count_by_color(&bricks);
+		assert_eq!(counts[3], 10);
+		assert_eq!(counts[7], 5);
+		assert_eq!(counts.iter().sum::<usize>(), 15);
+		assert_eq!(dominant_color(&bricks), Some(3));
+
+		let unused = unused_color_slots(&bricks);
+		assert_eq!(unused.len(), 62);
+		assert!(unused.contains(&0));
+		assert!(!unused.contains(&3) && !unused.contains(&7));
+
+		assert_eq!(dominant_color(&[]), None);
+		assert_eq!(unused_color_slots(&[]).len(), 64);
+	}
+
+	#[test]
+	fn dominant_color_prefers_lowest_index_on_ties() {
+		let mut bricks = vec![brick("1x1", (0.0, 0.0, 0.2)), brick("1x1", (1.0, 0.0, 0.2))];
+		bricks[0].base.color_index = 9;
+		bricks[1].base.color_index = 4;
+		assert_eq!(dominant_color(&bricks), Some(4));
+
+		bricks[1].base.color_index = 64;
+		assert_eq!(count_by_color(&bricks).iter().sum::<usize>(), 1);
+		assert_eq!(dominant_color(&bricks), Some(9));
+	}
+
+	#[test]
+	fn owned_by_ignores_case_and_skips_unowned_bricks() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"+-OWNER Builder",
+			&fixture::line("B", (1.0, 0.0, 0.2)),
+			&fixture::line("C", (2.0, 0.0, 0.2)),
+			"+-OWNER builder",
+			&fixture::line("D", (3.0, 0.0, 0.2)),
+			"+-OWNER Other",
+		]));
+		let names: Vec<&str> = owned_by(&bricks, "BUILDER")
+			.map(|brick| brick.base.ui_name.as_str())
+			.collect();
+		assert_eq!(names, vec!["A", "C"]);
+		assert_eq!(owned_by(&bricks, "").count(), 0);
+	}
+}
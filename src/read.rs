@@ -1,77 +1,99 @@
-use crate::{data::BrickBase, escape::collapse, Brick};
-use std::io::{self, prelude::*};
-use std::iter::Peekable;
+use crate::{
+	data::{BrickBase, Color, Colors},
+	escape::collapse,
+	Brick,
+};
+use std::io::{self, prelude::*, Cursor};
+use std::str::FromStr;
+use std::{error, fmt};
 
+const HEADER: &str =
+	"This is a Blockland save file.  You probably shouldn't modify it cause you'll screw it up.";
 const LINECOUNT_PREFIX: &str = "Linecount ";
 const EXTRA_DATA_PREFIX: &str = "+-";
-
-type Color = (f32, f32, f32, f32);
-type Colors = [Color; 64];
+const OWNER_PREFIX: &str = "+-OWNER ";
+const DEFAULT_MAX_DESCRIPTION_LINES: usize = 1000;
 
 /// Reads save files.
 ///
 /// Metadata including the description, colors and usually the brick count
 /// is available on construction. Iterating over the reader yields the bricks.
 ///
+/// `Reader` is itself an `Iterator`, which is what makes
+/// `for brick in reader` work. Use [`into_bricks`](#method.into_bricks) for
+/// a separately named iterator type without the metadata.
 pub struct Reader<R: BufRead> {
-	brick_data: Peekable<BrickDataParser<Cp1252Lines<R>>>,
+	brick_data: BrickDataParser<R>,
 	description: String,
 	colors: Colors,
 	brick_count: Option<usize>,
+	bricks_yielded: usize,
+	line_count: usize,
+	on_error: Option<ErrorHandler>,
+	max_brick_count: usize,
+	limit_exceeded: bool,
 }
 
+type ErrorHandler = Box<dyn FnMut(&io::Error) + Send>;
+
 impl<R: BufRead> Reader<R> {
 	/// Construct a new instance from a
 	/// [`BufRead`](https://doc.rust-lang.org/std/io/trait.BufRead.html) source
 	/// and immediately read metadata.
 	///
-	/// ```rust
+	/// ```rust,no_run
+	/// # use std::{fs::File, io::BufReader};
+	/// # fn main() -> std::io::Result<()> {
 	/// let file = BufReader::new(File::open("House.bls")?);
 	/// let reader = bl_save::Reader::new(file)?;
+	/// # Ok(())
+	/// # }
 	/// ```
 	pub fn new(r: R) -> io::Result<Self> {
-		let mut lines = cp1252_lines(r);
+		ReaderBuilder::new(r).build()
+	}
 
-		// This is a Blockland save file.
-		// You probably shouldn't modify it cause you'll screw it up.
-		read_line(&mut lines)?;
+	/// Construct a new instance from a source whose first line has already
+	/// been consumed, such as by [`probe`](fn.probe.html) or
+	/// [`is_valid_header`](fn.is_valid_header.html).
+	pub fn after_header(r: R) -> io::Result<Self> {
+		let mut lines = cp1252_lines(r);
+		lines.line_count = 1;
+		Self::from_lines(lines, ReadOptions::default())
+	}
 
+	fn from_lines(mut lines: Cp1252Lines<R>, options: ReadOptions) -> io::Result<Self> {
 		// Description.
-		let description_line_count = read_line(&mut lines)?.parse().unwrap_or(0);
-		if description_line_count > 1000 {
-			return Err(invalid_data("Description is unreasonably long"));
-		}
-		let mut description_escaped = String::new();
-		for line_index in 0..description_line_count {
-			if line_index > 0 {
-				description_escaped.push('\n');
-			}
-			description_escaped.push_str(&read_line(&mut lines)?);
-		}
-		let mut description = String::new();
-		collapse(&mut description, description_escaped.chars());
+		let description_escaped =
+			read_description_escaped(&mut lines, options.limits.max_description_lines)?;
+		let description = (options.description_decoder)(&description_escaped);
 
 		// Colors.
 		let mut colors = [Default::default(); 64];
 		for color in colors.iter_mut() {
-			let line = read_line(&mut lines)?;
-			let mut chars = line.chars();
-			let r = float_from_chars(&mut chars);
-			let g = float_from_chars(&mut chars);
-			let b = float_from_chars(&mut chars);
-			let a = float_from_chars(&mut chars);
-			*color = (r, g, b, a);
+			*color = parse_color(&read_line(&mut lines)?);
 		}
 
-		let mut brick_data = BrickDataParser(lines).peekable();
+		let mut line_count = lines.line_count;
+		let mut brick_data = BrickDataParser::new(lines);
 
 		// Get the brick count early, if possible. It's usually the first line.
 		let mut brick_count = None;
 
-		if let Some(Ok(BrickLine::Linecount(_))) | Some(Err(_)) = brick_data.peek() {
+		// With error recovery, a bad first brick line is left for iteration
+		// instead of failing construction.
+		let take_first = match brick_data.peek() {
+			Some((_, Ok(BrickLine::Linecount(_)))) => true,
+			Some((_, Err(_))) => !options.error_recovery,
+			_ => false,
+		};
+		if take_first {
 			match brick_data.next() {
-				Some(Ok(BrickLine::Linecount(count))) => brick_count = Some(count),
-				Some(Err(e)) => return Err(e),
+				Some((line, Ok(BrickLine::Linecount(count)))) => {
+					brick_count = Some(count);
+					line_count = line;
+				}
+				Some((_, Err(e))) => return Err(e),
 				_ => unreachable!(),
 			}
 		}
@@ -81,15 +103,26 @@ impl<R: BufRead> Reader<R> {
 			description,
 			colors,
 			brick_count,
+			bricks_yielded: 0,
+			line_count,
+			on_error: options.on_error(),
+			max_brick_count: options.limits.max_brick_count,
+			limit_exceeded: false,
 		})
 	}
 
 	/// The description of the save file.
-	/// The reader will refuse to read more than 1,000 lines.
+	/// By default, the reader will refuse to read more than 1,000 lines.
+	/// See [`ReadLimits`](struct.ReadLimits.html).
 	pub fn description(&self) -> &str {
 		&self.description
 	}
 
+	/// The lines of the description.
+	pub fn description_lines(&self) -> impl Iterator<Item = &str> {
+		self.description.lines()
+	}
+
 	/// The colorset used by bricks in the save file.
 	pub fn colors(&self) -> &Colors {
 		&self.colors
@@ -100,81 +133,590 @@ impl<R: BufRead> Reader<R> {
 	/// The reader will attempt to make this available on construction,
 	/// but that isn't guaranteed if the brick count is in a non-standard location or absent.
 	/// It may become available during brick iteration.
+	///
+	/// The reader's `size_hint` uses this as its upper bound.
 	pub fn brick_count(&self) -> Option<usize> {
 		self.brick_count
 	}
+
+	/// The number of lines consumed from the source so far, up to and
+	/// including the last line of the most recently yielded brick.
+	pub fn line_count(&self) -> usize {
+		self.line_count
+	}
+
+	/// Skip bricks that fail to parse, passing their errors to `handler`
+	/// instead of yielding them.
+	///
+	/// Only parse errors are handled this way; I/O errors from the source
+	/// are still yielded.
+	///
+	/// The handler is stored in the reader and must be `'static`, so it
+	/// can't borrow local variables. To collect the errors, share a
+	/// collection with the handler:
+	///
+	/// ```rust
+	/// # use std::sync::{Arc, Mutex};
+	/// # fn main() -> std::io::Result<()> {
+	/// # let data = format!(
+	/// #     "This is a Blockland save file.  You probably shouldn't modify it cause you'll screw it up.\r\n0\r\n{}Linecount 2\r\n1x1\" 0 0 0 0 0 0  0 0 1 1 1\r\nNo quote\r\n",
+	/// #     "1 1 1 1\r\n".repeat(64),
+	/// # );
+	/// let errors = Arc::new(Mutex::new(Vec::new()));
+	/// let handler_errors = Arc::clone(&errors);
+	/// let reader = bl_save::Reader::from_str(&data)?
+	///     .with_on_error(move |e| handler_errors.lock().unwrap().push(e.to_string()));
+	///
+	/// for brick in reader {
+	///     let brick = brick?;
+	/// }
+	/// assert_eq!(errors.lock().unwrap().len(), 1);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_on_error<F>(mut self, handler: F) -> Self
+	where
+		F: FnMut(&io::Error) + Send + 'static,
+	{
+		self.on_error = Some(Box::new(handler));
+		self
+	}
+
+	/// Read all remaining bricks while threading state through `f`.
+	///
+	/// `f` receives each brick along with the state and returns the brick to
+	/// keep, or `None` to drop it. Returns the final state and the kept
+	/// bricks. Stops at the first error.
+	///
+	/// ```rust,no_run
+	/// # use std::{fs::File, io::BufReader};
+	/// # fn main() -> std::io::Result<()> {
+	/// let file = BufReader::new(File::open("House.bls")?);
+	/// let (count, bricks) = bl_save::Reader::new(file)?.fold_bricks(0, |index, mut brick| {
+	///     brick.base.ui_name = format!("{} {}", brick.base.ui_name, index);
+	///     *index += 1;
+	///     Some(brick)
+	/// })?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn fold_bricks<S, F>(self, initial: S, mut f: F) -> io::Result<(S, Vec<Brick>)>
+	where
+		F: FnMut(&mut S, Brick) -> Option<Brick>,
+	{
+		let mut state = initial;
+		let mut bricks = Vec::new();
+		for brick in self {
+			if let Some(brick) = f(&mut state, brick?) {
+				bricks.push(brick);
+			}
+		}
+		Ok((state, bricks))
+	}
+
+	pub(crate) fn bricks_yielded(&self) -> usize {
+		self.bricks_yielded
+	}
+
+	/// Skip brick lines positioned outside the inclusive box `[min, max]`
+	/// without parsing more than their position.
+	pub(crate) fn set_position_filter(&mut self, min: (f32, f32, f32), max: (f32, f32, f32)) {
+		self.brick_data.position_filter = Some((min, max));
+	}
+
+	fn next_brick_line(&mut self) -> Option<io::Result<BrickLine>> {
+		let (line, result) = self.brick_data.next()?;
+		self.line_count = line;
+		Some(result)
+	}
 }
 
+/// The upper bound of `size_hint` is derived from the claimed brick count
+/// and is therefore not guaranteed to be correct either.
 impl<R: BufRead> Iterator for Reader<R> {
 	type Item = io::Result<Brick>;
 
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self.brick_count {
+			Some(count) => (0, Some(count.saturating_sub(self.bricks_yielded))),
+			None => (0, None),
+		}
+	}
+
 	fn next(&mut self) -> Option<Self::Item> {
+		if self.limit_exceeded {
+			return None;
+		}
+
 		loop {
-			let first = match self.brick_data.next() {
+			let first = match self.next_brick_line() {
 				Some(Ok(BrickLine::Base(data))) => data,
-				Some(Ok(BrickLine::Extra(_))) => {
-					panic!("previous iteration should have handled extra brick data")
-				}
+				// Extra data is consumed along with its brick, unless the brick
+				// line itself failed to parse.
+				Some(Ok(BrickLine::Extra(_))) => continue,
+				// Its extra data is skipped the same way.
+				Some(Ok(BrickLine::Skipped)) => continue,
 				Some(Ok(BrickLine::Linecount(count))) => {
 					self.brick_count = Some(count);
 					continue;
 				}
-				Some(Err(e)) => return Some(Err(e)),
+				Some(Err(e)) => match &mut self.on_error {
+					Some(handler) if e.kind() == io::ErrorKind::InvalidData => {
+						handler(&e);
+						continue;
+					}
+					_ => return Some(Err(e)),
+				},
 				None => return None,
 			};
 
+			if self.bricks_yielded >= self.max_brick_count {
+				self.limit_exceeded = true;
+				return Some(Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					LimitExceeded {
+						limit_name: "max_brick_count",
+						value: self.max_brick_count,
+					},
+				)));
+			}
+
 			let mut brick = Brick {
 				base: first,
+				owner: None,
 				unknown_extra: Vec::new(),
 			};
 
-			loop {
-				match self.brick_data.peek() {
-					Some(Ok(BrickLine::Extra(_))) => {}
-					Some(Ok(_)) | None => break,
-					Some(Err(_)) => {
-						let e = match self.brick_data.next() {
-							Some(Err(e)) => e,
-							_ => panic!("variant changed from peek() to next()"),
-						};
-						return Some(Err(e));
-					}
-				}
-
-				let extra = match self.brick_data.next() {
+			// Anything other than extra data, including errors, is left for
+			// the next iteration.
+			while let Some((_, Ok(BrickLine::Extra(_)))) = self.brick_data.peek() {
+				let extra = match self.next_brick_line() {
 					Some(Ok(BrickLine::Extra(extra))) => extra,
 					_ => panic!("variant changed from peek() to next()"),
 				};
 
 				match extra {
+					BrickExtra::Owner(owner) => brick.owner = Some(owner),
 					BrickExtra::Unknown(s) => brick.unknown_extra.push(s),
 				}
 			}
 
+			self.bricks_yielded += 1;
 			return Some(Ok(brick));
 		}
 	}
 }
 
+impl<'a> Reader<Cursor<&'a [u8]>> {
+	/// Construct a new instance from save data in memory.
+	pub fn from_bytes(data: &'a [u8]) -> io::Result<Self> {
+		Self::new(Cursor::new(data))
+	}
+
+	/// Construct a new instance from save data in a string.
+	///
+	/// Save files are Windows-1252, so non-ASCII characters in `data` are
+	/// read as their UTF-8 bytes rather than as themselves.
+	///
+	/// ```rust
+	/// # fn main() -> std::io::Result<()> {
+	/// let reader = bl_save::Reader::from_str(
+	///     "This is a Blockland save file.  You probably shouldn't modify it cause you'll screw it up.\r\n\
+	///      1\r\n\
+	///      My build\r\n",
+	/// )?;
+	/// assert_eq!(reader.description(), "My build");
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[allow(clippy::should_implement_trait)]
+	pub fn from_str(data: &'a str) -> io::Result<Self> {
+		Self::from_bytes(data.as_bytes())
+	}
+}
+
+/// Configures and constructs a [`Reader`](struct.Reader.html).
+///
+/// `ReaderBuilder::new(r).build()` is equivalent to `Reader::new(r)`.
+///
+/// ```rust,no_run
+/// # use std::{fs::File, io::BufReader};
+/// # fn main() -> std::io::Result<()> {
+/// let file = BufReader::new(File::open("House.bls")?);
+/// let reader = bl_save::ReaderBuilder::new(file)
+///     .description_decoder(str::to_owned)
+///     .error_recovery(true)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReaderBuilder<R> {
+	r: R,
+	options: ReadOptions,
+}
+
+impl<R: BufRead> ReaderBuilder<R> {
+	/// Start configuring a reader for a
+	/// [`BufRead`](https://doc.rust-lang.org/std/io/trait.BufRead.html) source.
+	pub fn new(r: R) -> Self {
+		Self {
+			r,
+			options: ReadOptions::default(),
+		}
+	}
+
+	/// Replace how the description is decoded. The decoder receives the
+	/// description lines joined by `\n`, with escape sequences such as `\n`
+	/// and `\c0` still in place.
+	///
+	/// By default, escape sequences are collapsed as Blockland does.
+	pub fn description_decoder(mut self, decoder: fn(&str) -> String) -> Self {
+		self.options.description_decoder = decoder;
+		self
+	}
+
+	/// Whether to skip bricks that fail to parse instead of yielding errors.
+	/// Disabled by default. See also
+	/// [`Reader::with_on_error`](struct.Reader.html#method.with_on_error).
+	pub fn error_recovery(mut self, enabled: bool) -> Self {
+		self.options.error_recovery = enabled;
+		self
+	}
+
+	/// Limit the number of description lines and bricks the reader accepts.
+	///
+	/// Shorthand for [`read_limits`](#method.read_limits).
+	pub fn limits(self, max_description_lines: usize, max_bricks: usize) -> Self {
+		self.read_limits(ReadLimits {
+			max_description_lines,
+			max_brick_count: max_bricks,
+		})
+	}
+
+	/// Set the limits the reader enforces. Defaults to
+	/// [`ReadLimits::default()`](struct.ReadLimits.html).
+	pub fn read_limits(mut self, limits: ReadLimits) -> Self {
+		self.options.limits = limits;
+		self
+	}
+
+	/// Construct the reader and immediately read metadata.
+	pub fn build(self) -> io::Result<Reader<R>> {
+		let mut lines = cp1252_lines(self.r);
+
+		// This is a Blockland save file.
+		// You probably shouldn't modify it cause you'll screw it up.
+		read_line(&mut lines)?;
+
+		Reader::from_lines(lines, self.options)
+	}
+}
+
+/// Limits on how much a [`Reader`](struct.Reader.html) accepts from a
+/// source.
+///
+/// Save files are plain text and cheap to craft. A short file can claim a
+/// huge description, and a large or generated one can contain millions of
+/// bricks, which exhausts memory in code that collects every brick.
+/// When reading files from untrusted sources, use
+/// [`strict`](#method.strict) or tighter limits.
+///
+/// Exceeding `max_description_lines` fails construction of the reader.
+/// Exceeding `max_brick_count` makes the reader yield a single error, which
+/// wraps a [`LimitExceeded`](struct.LimitExceeded.html), and then stop
+/// without reading further. Both errors have the kind
+/// [`InvalidData`](https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLimits {
+	/// The maximum number of description lines.
+	pub max_description_lines: usize,
+	/// The maximum number of bricks to read.
+	pub max_brick_count: usize,
+}
+
+impl ReadLimits {
+	/// Limits for untrusted sources: 100 description lines and 1,000,000
+	/// bricks.
+	pub fn strict() -> Self {
+		Self {
+			max_description_lines: 100,
+			max_brick_count: 1_000_000,
+		}
+	}
+
+	/// No limits at all. Only use this for trusted sources.
+	pub fn unlimited() -> Self {
+		Self {
+			max_description_lines: usize::MAX,
+			max_brick_count: usize::MAX,
+		}
+	}
+}
+
+/// 1,000 description lines and no brick limit, as used by
+/// [`Reader::new`](struct.Reader.html#method.new).
+impl Default for ReadLimits {
+	fn default() -> Self {
+		Self {
+			max_description_lines: DEFAULT_MAX_DESCRIPTION_LINES,
+			max_brick_count: usize::MAX,
+		}
+	}
+}
+
+/// The error wrapped by the `io::Error` a [`Reader`](struct.Reader.html)
+/// yields when it exceeds one of its [`ReadLimits`](struct.ReadLimits.html).
+///
+/// ```rust
+/// # let mut save = String::from("This is a Blockland save file.  You probably shouldn't modify it cause you'll screw it up.\r\n0\r\n");
+/// # for _ in 0..64 { save.push_str("1 1 1 1\r\n"); }
+/// # save.push_str("1x1\" 0 0 0 0 0 0  0 0 1 1 1\r\n1x1\" 1 0 0 0 0 0  0 0 1 1 1\r\n");
+/// let reader = bl_save::ReaderBuilder::new(save.as_bytes()).limits(1000, 1).build()?;
+/// let errors: Vec<std::io::Error> = reader.filter_map(Result::err).collect();
+///
+/// let limit = errors[0]
+///     .get_ref()
+///     .and_then(|e| e.downcast_ref::<bl_save::LimitExceeded>())
+///     .unwrap();
+/// assert_eq!(limit.limit_name, "max_brick_count");
+/// assert_eq!(limit.value, 1);
+/// assert_eq!(errors.len(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+	/// The name of the exceeded field of `ReadLimits`.
+	pub limit_name: &'static str,
+	/// The value of the limit.
+	pub value: usize,
+}
+
+impl fmt::Display for LimitExceeded {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "exceeded {} of {}", self.limit_name, self.value)
+	}
+}
+
+impl error::Error for LimitExceeded {}
+
+struct ReadOptions {
+	description_decoder: fn(&str) -> String,
+	error_recovery: bool,
+	limits: ReadLimits,
+}
+
+impl Default for ReadOptions {
+	fn default() -> Self {
+		Self {
+			description_decoder: collapse_description,
+			error_recovery: false,
+			limits: ReadLimits::default(),
+		}
+	}
+}
+
+impl ReadOptions {
+	fn on_error(&self) -> Option<ErrorHandler> {
+		if self.error_recovery {
+			Some(Box::new(|_| {}))
+		} else {
+			None
+		}
+	}
+}
+
+fn collapse_description(escaped: &str) -> String {
+	let mut description = String::new();
+	collapse(&mut description, escaped.chars());
+	description
+}
+
+/// Read only the description of a save file, without reading its colors
+/// or bricks.
+pub fn read_description_only(r: impl BufRead) -> io::Result<String> {
+	let mut lines = cp1252_lines(r);
+	read_line(&mut lines)?;
+
+	let description_escaped = read_description_escaped(&mut lines, DEFAULT_MAX_DESCRIPTION_LINES)?;
+	Ok(collapse_description(&description_escaped))
+}
+
+/// Read only the claimed brick count of a save file, skipping the
+/// description and colors and stopping at the first `Linecount` line.
+///
+/// Returns `None` if the file has no `Linecount` line, in which case the
+/// whole file is read.
+pub fn read_brick_count_only(r: impl BufRead) -> io::Result<Option<usize>> {
+	let mut lines = cp1252_lines(r);
+	read_line(&mut lines)?;
+	read_description_escaped(&mut lines, DEFAULT_MAX_DESCRIPTION_LINES)?;
+	for _ in 0..64 {
+		read_line(&mut lines)?;
+	}
+
+	for line in lines {
+		if let Some(count) = line?.strip_prefix(LINECOUNT_PREFIX) {
+			return Ok(Some(count.parse().unwrap_or(0)));
+		}
+	}
+
+	Ok(None)
+}
+
+/// The result of [`probe`](fn.probe.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeResult {
+	/// The first line is the standard save file header.
+	Valid,
+	/// The first line is something else, such as a truncated header.
+	InvalidHeader(String),
+	/// The source has no lines at all.
+	Empty,
+}
+
+/// Read only the first line of a source and check whether it is the
+/// standard save file header.
+///
+/// The source is left positioned at the second line. Continue reading
+/// with [`Reader::after_header`](struct.Reader.html#method.after_header).
+pub fn probe(r: &mut impl BufRead) -> io::Result<ProbeResult> {
+	match cp1252_lines(r).next() {
+		Some(line) => {
+			let line = line?;
+			if line.trim_end() == HEADER {
+				Ok(ProbeResult::Valid)
+			} else {
+				Ok(ProbeResult::InvalidHeader(line))
+			}
+		}
+		None => Ok(ProbeResult::Empty),
+	}
+}
+
+/// Like [`probe`](fn.probe.html), but only reports whether the header is
+/// valid.
+pub fn is_valid_header(r: &mut impl BufRead) -> io::Result<bool> {
+	Ok(probe(r)? == ProbeResult::Valid)
+}
+
+/// Read the description line count and the description lines, without
+/// collapsing escape sequences.
+fn read_description_escaped(
+	mut lines: impl Iterator<Item = io::Result<String>>,
+	max_lines: usize,
+) -> io::Result<String> {
+	let line = read_line(&mut lines)?;
+	let description_line_count = line.parse().unwrap_or(0);
+	if description_line_count > max_lines {
+		// The line count always follows the header line.
+		let error = format!("Description exceeds the limit of {} lines", max_lines);
+		return Err(parse_error(2, &error, &line));
+	}
+	let mut description_escaped = String::new();
+	for line_index in 0..description_line_count {
+		if line_index > 0 {
+			description_escaped.push('\n');
+		}
+		// Stop instead of padding with empty lines, since the claimed count
+		// may be far larger than the file.
+		let line = lines.next().unwrap_or_else(|| {
+			Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				format!(
+					"Save file ends after {} of {} description lines",
+					line_index, description_line_count
+				),
+			))
+		})?;
+		description_escaped.push_str(&line);
+	}
+	Ok(description_escaped)
+}
+
 fn read_line(mut lines: impl Iterator<Item = io::Result<String>>) -> io::Result<String> {
 	lines.next().unwrap_or_else(|| Ok(String::from("")))
 }
 
-struct BrickDataParser<L>(L);
+/// A parsed line along with its line number.
+type ParsedLine = (usize, io::Result<BrickLine>);
+/// The `(min, max)` corners of an inclusive box.
+type PositionBox = ((f32, f32, f32), (f32, f32, f32));
+
+struct BrickDataParser<R> {
+	lines: Cp1252Lines<R>,
+	peeked: Option<ParsedLine>,
+	position_filter: Option<PositionBox>,
+}
+
+impl<R: BufRead> BrickDataParser<R> {
+	fn new(lines: Cp1252Lines<R>) -> Self {
+		Self {
+			lines,
+			peeked: None,
+			position_filter: None,
+		}
+	}
+
+	fn peek(&mut self) -> Option<&ParsedLine> {
+		if self.peeked.is_none() {
+			self.peeked = self.parse_next();
+		}
+		self.peeked.as_ref()
+	}
 
-impl<L: Iterator<Item = io::Result<String>>> Iterator for BrickDataParser<L> {
-	type Item = io::Result<BrickLine>;
+	fn parse_next(&mut self) -> Option<ParsedLine> {
+		let line_number = self.lines.line_count + 1;
+		let filter = self.position_filter;
+		let result = self.lines.next()?.and_then(|line| {
+			if let Some((min, max)) = filter {
+				if is_brick_line_outside(&line, min, max) {
+					return Ok(BrickLine::Skipped);
+				}
+			}
+			parse_brick_data_line(&line)
+				.map_err(|e| parse_error(line_number, &e.to_string(), &line))
+		});
+		Some((self.lines.line_count, result))
+	}
+}
+
+impl<R: BufRead> Iterator for BrickDataParser<R> {
+	type Item = ParsedLine;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		self.0.next().map(|r| r.and_then(parse_brick_data_line))
+		self.peeked.take().or_else(|| self.parse_next())
+	}
+}
+
+/// Whether `line` is a brick line positioned outside the inclusive box
+/// `[min, max]`, parsing only as far as the position.
+fn is_brick_line_outside(line: &str, min: (f32, f32, f32), max: (f32, f32, f32)) -> bool {
+	if line.starts_with(EXTRA_DATA_PREFIX) || line.starts_with(LINECOUNT_PREFIX) {
+		return false;
 	}
+	// Malformed lines are left for the full parser to report.
+	let mut chars = match line.find('"') {
+		Some(index) => line[index + '"'.len_utf8()..].chars(),
+		None => return false,
+	};
+	if chars.next() != Some(' ') {
+		return false;
+	}
+	let position = (
+		float_from_chars(&mut chars),
+		float_from_chars(&mut chars),
+		float_from_chars(&mut chars),
+	);
+	!crate::query::in_box(position, min, max)
 }
 
-fn parse_brick_data_line(line: String) -> io::Result<BrickLine> {
-	if line.starts_with(EXTRA_DATA_PREFIX) {
-		Ok(BrickLine::Extra(BrickExtra::Unknown(line)))
-	} else if line.starts_with(LINECOUNT_PREFIX) {
-		let brick_count = line[LINECOUNT_PREFIX.len()..].parse().unwrap_or(0);
+fn parse_brick_data_line(line: &str) -> io::Result<BrickLine> {
+	if let Some(owner) = strip_prefix_ignore_ascii_case(line, OWNER_PREFIX) {
+		Ok(BrickLine::Extra(BrickExtra::Owner(String::from(owner))))
+	} else if line.starts_with(EXTRA_DATA_PREFIX) {
+		Ok(BrickLine::Extra(BrickExtra::Unknown(String::from(line))))
+	} else if let Some(count) = line.strip_prefix(LINECOUNT_PREFIX) {
+		let brick_count = count.parse().unwrap_or(0);
 		Ok(BrickLine::Linecount(brick_count))
 	} else {
 		let quote_index = line
@@ -217,20 +759,73 @@ fn parse_brick_data_line(line: String) -> io::Result<BrickLine> {
 	}
 }
 
+/// Parses a single brick line as it appears in a save file.
+/// Lines holding extra brick data or the brick count are rejected.
+///
+/// ```rust
+/// # fn main() -> std::io::Result<()> {
+/// let base: bl_save::BrickBase = "2x2 Plate\" 0 0 0.1 0 1 5  0 0 1 1 1".parse()?;
+/// assert_eq!(base.ui_name, "2x2 Plate");
+/// assert_eq!(base.position, (0.0, 0.0, 0.1));
+/// assert_eq!(base.color_index, 5);
+/// # Ok(())
+/// # }
+/// ```
+impl FromStr for BrickBase {
+	type Err = io::Error;
+
+	fn from_str(line: &str) -> io::Result<Self> {
+		match parse_brick_data_line(line)? {
+			BrickLine::Base(base) => Ok(base),
+			BrickLine::Extra(_) | BrickLine::Linecount(_) | BrickLine::Skipped => {
+				Err(invalid_data("Not a brick line"))
+			}
+		}
+	}
+}
+
 enum BrickLine {
 	Base(BrickBase),
 	Extra(BrickExtra),
 	Linecount(usize),
+	/// A brick line excluded by the position filter.
+	Skipped,
 }
 
 enum BrickExtra {
+	Owner(String),
 	Unknown(String),
 }
 
+/// Like `str::strip_prefix`, but ignoring ASCII case as Blockland does for
+/// extra data keys.
+fn strip_prefix_ignore_ascii_case<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+	match line.get(..prefix.len()) {
+		Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&line[prefix.len()..]),
+		_ => None,
+	}
+}
+
 fn invalid_data(error: &str) -> io::Error {
 	io::Error::new(io::ErrorKind::InvalidData, error)
 }
 
+/// An error for a line of the file that couldn't be parsed, including the
+/// 1-based line number and the start of the line.
+fn parse_error(line_number: usize, error: &str, line: &str) -> io::Error {
+	const MAX_CHARS: usize = 120;
+
+	let mut excerpt: String = line.chars().take(MAX_CHARS).collect();
+	if excerpt.len() < line.len() {
+		excerpt.push_str("...");
+	}
+
+	invalid_data(&format!(
+		"parse error at line {}: {}: {:?}",
+		line_number, error, excerpt
+	))
+}
+
 fn expect_next<T>(iter: &mut impl Iterator<Item = T>, error: &str) -> io::Result<T> {
 	iter.next().ok_or_else(|| invalid_data(error))
 }
@@ -250,6 +845,16 @@ fn take_word_consume_space(iter: &mut impl Iterator<Item = char>) -> String {
 	iter.take_while(|c| *c != ' ').collect()
 }
 
+/// Parse a colorset line of the form `R G B A`.
+pub(crate) fn parse_color(line: &str) -> Color {
+	let mut chars = line.chars();
+	let r = float_from_chars(&mut chars);
+	let g = float_from_chars(&mut chars);
+	let b = float_from_chars(&mut chars);
+	let a = float_from_chars(&mut chars);
+	(r, g, b, a)
+}
+
 fn float_from_chars(chars: &mut impl Iterator<Item = char>) -> f32 {
 	take_word_consume_space(chars).parse().unwrap_or(0.0)
 }
@@ -263,19 +868,26 @@ fn bool_from_chars(chars: &mut impl Iterator<Item = char>) -> bool {
 }
 
 fn cp1252_lines<R: BufRead>(r: R) -> Cp1252Lines<R> {
-	Cp1252Lines(r)
+	Cp1252Lines {
+		inner: r,
+		line_count: 0,
+	}
 }
 
-struct Cp1252Lines<R>(R);
+struct Cp1252Lines<R> {
+	inner: R,
+	line_count: usize,
+}
 
 impl<R: BufRead> Iterator for Cp1252Lines<R> {
 	type Item = io::Result<String>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		let mut buf = Vec::new();
-		match self.0.read_until(b'\n', &mut buf) {
+		match self.inner.read_until(b'\n', &mut buf) {
 			Ok(0) => None,
 			Ok(_n) => {
+				self.line_count += 1;
 				if buf.iter().last() == Some(&b'\n') {
 					buf.pop();
 					if buf.iter().last() == Some(&b'\r') {
@@ -291,3 +903,430 @@ impl<R: BufRead> Iterator for Cp1252Lines<R> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+	use std::sync::{Arc, Mutex};
+
+	#[test]
+	fn unlimited_description_stops_at_end_of_input() {
+		let save = format!("{}\r\n99999999999\r\nOnly line\r\n", fixture::HEADER);
+		let result = ReaderBuilder::new(save.as_bytes())
+			.read_limits(ReadLimits::unlimited())
+			.build();
+		match result {
+			Err(e) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+			Ok(_) => panic!("read a description longer than the file"),
+		}
+	}
+
+	#[test]
+	fn description_is_read_up_to_claimed_count() {
+		let save = fixture::save_with_description(&["First", "Second"], &[]);
+		let reader = ReaderBuilder::new(save.as_bytes())
+			.read_limits(ReadLimits::unlimited())
+			.build()
+			.unwrap();
+		assert_eq!(reader.description(), "First\nSecond");
+		assert_eq!(reader.brick_count(), Some(0));
+	}
+
+	#[test]
+	fn bricks_follow_description() {
+		let save = fixture::save(&[&fixture::line("1x1", (0.0, 0.0, 0.2))]);
+		let bricks = Reader::from_str(&save).unwrap().collect::<io::Result<Vec<_>>>().unwrap();
+		assert_eq!(bricks.len(), 1);
+		assert_eq!(bricks[0].base.ui_name, "1x1");
+	}
+
+	#[test]
+	fn owner_prefix_ignores_case() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"+-OWNER 5",
+			&fixture::line("B", (0.0, 0.0, 0.2)),
+			"+-owner 6",
+			"+-NTOBJECTNAME _door",
+		]);
+		let bricks = Reader::from_str(&save).unwrap().collect::<io::Result<Vec<_>>>().unwrap();
+		assert_eq!(bricks[0].owner(), Some("5"));
+		assert!(bricks[0].unknown_extra.is_empty());
+		assert_eq!(bricks[1].owner(), Some("6"));
+		assert_eq!(bricks[1].unknown_extra, vec!["+-NTOBJECTNAME _door"]);
+		assert_eq!(bricks[1].get_extra("OWNER"), None);
+	}
+
+	#[test]
+	fn extras_of_a_broken_brick_line_are_dropped() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"No quote",
+			"+-OWNER 1",
+			"+-NTOBJECTNAME _orphan",
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+		]);
+
+		let mut reader = Reader::from_str(&save).unwrap();
+		assert_eq!(reader.next().unwrap().unwrap().base.ui_name, "A");
+		assert!(reader.next().unwrap().is_err());
+		let b = reader.next().unwrap().unwrap();
+		assert_eq!(b.base.ui_name, "B");
+		assert_eq!(b.owner(), None);
+		assert!(b.unknown_extra.is_empty());
+		assert!(reader.next().is_none());
+	}
+
+	#[test]
+	fn on_error_skips_broken_bricks_and_their_extras() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"No quote",
+			"+-OWNER 1",
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+		]);
+
+		let errors = Arc::new(Mutex::new(Vec::new()));
+		let handler_errors = Arc::clone(&errors);
+		let bricks = Reader::from_str(&save)
+			.unwrap()
+			.with_on_error(move |e| handler_errors.lock().unwrap().push(e.to_string()))
+			.collect::<io::Result<Vec<_>>>()
+			.unwrap();
+
+		assert_eq!(bricks.len(), 2);
+		assert_eq!(bricks[1].owner(), None);
+		assert_eq!(errors.lock().unwrap().len(), 1);
+	}
+
+	#[test]
+	fn brick_before_a_broken_line_is_yielded_with_its_extras() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"+-OWNER 1",
+			"No quote",
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+		]);
+
+		let mut reader = Reader::from_str(&save).unwrap();
+		let a = reader.next().unwrap().unwrap();
+		assert_eq!(a.base.ui_name, "A");
+		assert_eq!(a.owner(), Some("1"));
+		assert!(reader.next().unwrap().is_err());
+		assert_eq!(reader.next().unwrap().unwrap().base.ui_name, "B");
+	}
+
+	#[test]
+	fn line_count_follows_bricks() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+			&fixture::line("C", (0.0, 0.0, 0.6)),
+		]);
+
+		// Header, description count, description, 64 colors and Linecount.
+		let header_lines = 68;
+		let mut reader = Reader::from_str(&save).unwrap();
+		assert_eq!(reader.line_count(), header_lines);
+		for _ in 0..3 {
+			reader.next().unwrap().unwrap();
+		}
+		assert_eq!(reader.line_count(), header_lines + 3);
+		assert!(reader.next().is_none());
+	}
+
+	#[test]
+	fn line_count_includes_extra_data() {
+		let save = fixture::save(&[&fixture::line("A", (0.0, 0.0, 0.2)), "+-OWNER 1"]);
+		let mut reader = Reader::from_str(&save).unwrap();
+		reader.next().unwrap().unwrap();
+		assert_eq!(reader.line_count(), 70);
+	}
+
+	#[test]
+	fn parse_errors_name_the_line() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+			"+-OWNER 1",
+			&fixture::line("C", (0.0, 0.0, 0.6)),
+			"No quote",
+		]);
+
+		let error = Reader::from_str(&save)
+			.unwrap()
+			.collect::<io::Result<Vec<_>>>()
+			.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"parse error at line 73: Invalid brick line: \"No quote\""
+		);
+	}
+
+	#[test]
+	fn identity_decoder_keeps_escapes() {
+		let save = fixture::save_with_description(&["Line\\none", "\\c1Red"], &[]);
+
+		let reader = ReaderBuilder::new(save.as_bytes())
+			.description_decoder(str::to_owned)
+			.build()
+			.unwrap();
+		assert_eq!(reader.description(), "Line\\none\n\\c1Red");
+
+		let reader = Reader::from_str(&save).unwrap();
+		assert_eq!(reader.description(), "Line\none\n\u{2}Red");
+	}
+
+	#[test]
+	fn error_recovery_leaves_bad_first_brick_for_iteration() {
+		let save = fixture::save(&["No quote", &fixture::line("A", (0.0, 0.0, 0.2))])
+			.replace("Linecount 2\r\n", "");
+
+		assert!(Reader::from_str(&save).is_err());
+
+		let bricks = ReaderBuilder::new(save.as_bytes())
+			.error_recovery(true)
+			.build()
+			.unwrap()
+			.collect::<io::Result<Vec<_>>>()
+			.unwrap();
+		assert_eq!(bricks.len(), 1);
+		assert_eq!(bricks[0].base.ui_name, "A");
+	}
+
+	#[test]
+	fn size_hint_counts_down_from_brick_count() {
+		let save = fixture::numbered(3);
+		let mut reader = Reader::from_bytes(save.as_bytes()).unwrap();
+		assert_eq!(reader.size_hint(), (0, Some(3)));
+		reader.next().unwrap().unwrap();
+		assert_eq!(reader.size_hint(), (0, Some(2)));
+		assert_eq!(reader.by_ref().count(), 2);
+		assert_eq!(reader.size_hint(), (0, Some(0)));
+	}
+
+	#[test]
+	fn size_hint_without_brick_count() {
+		let save = fixture::numbered(3).replace("Linecount 3\r\n", "");
+		let reader = Reader::from_bytes(save.as_bytes()).unwrap();
+		assert_eq!(reader.size_hint(), (0, None));
+		assert_eq!(fixture::read(&save).len(), 3);
+	}
+
+	#[test]
+	fn probe_valid_header_then_read() {
+		let save = fixture::numbered(2);
+		let mut source = save.as_bytes();
+		assert_eq!(probe(&mut source).unwrap(), ProbeResult::Valid);
+
+		let reader = Reader::after_header(source).unwrap();
+		assert_eq!(reader.description(), "Test");
+		assert_eq!(reader.line_count(), 68);
+		assert_eq!(reader.count(), 2);
+	}
+
+	#[test]
+	fn probe_rejects_other_files() {
+		let truncated = &fixture::HEADER[..20];
+		assert_eq!(
+			probe(&mut truncated.as_bytes()).unwrap(),
+			ProbeResult::InvalidHeader(String::from(truncated))
+		);
+		assert!(!is_valid_header(&mut "GIF89a\r\nrest".as_bytes()).unwrap());
+		assert_eq!(probe(&mut "".as_bytes()).unwrap(), ProbeResult::Empty);
+		assert!(is_valid_header(&mut fixture::numbered(0).as_bytes()).unwrap());
+	}
+
+	#[test]
+	fn parse_errors_truncate_long_lines() {
+		let long_line = "x".repeat(200);
+		let save = fixture::save_with_description(&["One", "Two", "Three"], &[&long_line]);
+
+		let error = Reader::from_str(&save).unwrap().next().unwrap().unwrap_err();
+		let expected = format!(
+			"parse error at line 71: Invalid brick line: \"{}...\"",
+			"x".repeat(120)
+		);
+		assert_eq!(error.to_string(), expected);
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn description_limit_error_names_line_two() {
+		let save = fixture::save_with_description(&["One", "Two"], &[]);
+		let error = ReaderBuilder::new(save.as_bytes()).limits(1, 10).build().err().unwrap();
+		assert_eq!(
+			error.to_string(),
+			"parse error at line 2: Description exceeds the limit of 1 lines: \"2\""
+		);
+	}
+
+	#[test]
+	fn brick_base_from_str() {
+		let base: BrickBase = "1x2F Print\" 2 -2.5 0.4 3 0 7 Letters/A 3 1 0 1 1"
+			.parse()
+			.unwrap();
+		assert_eq!(
+			base,
+			BrickBase {
+				ui_name: String::from("1x2F Print"),
+				position: (2.0, -2.5, 0.4),
+				angle: 3,
+				is_baseplate: false,
+				color_index: 7,
+				print: String::from("Letters/A"),
+				color_fx: 3,
+				shape_fx: 1,
+				raycasting: false,
+				collision: true,
+				rendering: true,
+			}
+		);
+	}
+
+	#[test]
+	fn brick_base_from_str_matches_reader() {
+		let line = "32x32 Base\" 0 0 0.1 0 1 3  0 0 1 1 1";
+		let bricks = fixture::read(&fixture::save(&[line]));
+		assert_eq!(line.parse::<BrickBase>().unwrap(), bricks[0].base);
+	}
+
+	#[test]
+	fn brick_base_from_str_rejects_other_lines() {
+		for line in &["+-OWNER 5", "Linecount 3", "No quote", "1x1\"0 0 0"] {
+			assert!(line.parse::<BrickBase>().is_err(), "{:?}", line);
+		}
+	}
+
+	#[test]
+	fn fold_bricks_numbers_and_drops_bricks() {
+		let save = fixture::save(&[
+			&fixture::line("1x1", (0.0, 0.0, 0.2)),
+			&fixture::line("2x2", (1.0, 0.0, 0.2)),
+			&fixture::line("1x1", (2.0, 0.0, 0.2)),
+		]);
+		let (count, bricks) = Reader::from_str(&save)
+			.unwrap()
+			.fold_bricks(0, |index, mut brick| {
+				brick.base.ui_name = format!("{} {}", brick.base.ui_name, index);
+				*index += 1;
+				Some(brick).filter(|brick| brick.base.ui_name != "2x2 1")
+			})
+			.unwrap();
+
+		assert_eq!(count, 3);
+		let names: Vec<&str> = bricks.iter().map(|b| b.base.ui_name.as_str()).collect();
+		assert_eq!(names, vec!["1x1 0", "1x1 2"]);
+	}
+
+	#[test]
+	fn fold_bricks_stops_at_first_error() {
+		let save = fixture::save(&[
+			&fixture::line("1x1", (0.0, 0.0, 0.2)),
+			"broken line",
+			&fixture::line("1x1", (2.0, 0.0, 0.2)),
+		]);
+		let mut seen = 0;
+		let result = Reader::from_str(&save).unwrap().fold_bricks((), |_, brick| {
+			seen += 1;
+			Some(brick)
+		});
+		assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+		assert_eq!(seen, 1);
+	}
+
+	#[test]
+	fn metadata_only_reads_agree_with_reader() {
+		let saves = vec![
+			fixture::numbered(3),
+			fixture::save_with_description(&["First", "Second\\nline"], &[]),
+		];
+		for save in &saves {
+			let reader = Reader::from_str(save).unwrap();
+			assert_eq!(read_description_only(save.as_bytes()).unwrap(), reader.description());
+			assert_eq!(read_brick_count_only(save.as_bytes()).unwrap(), reader.brick_count());
+		}
+		assert_eq!(read_brick_count_only(saves[0].as_bytes()).unwrap(), Some(3));
+		assert_eq!(read_description_only(saves[1].as_bytes()).unwrap(), "First\nSecond\nline");
+	}
+
+	#[test]
+	fn brick_count_only_without_linecount() {
+		let save = fixture::save(&[&fixture::line("1x1", (0.0, 0.0, 0.2))])
+			.replace("Linecount 1\r\n", "");
+		assert_eq!(read_brick_count_only(save.as_bytes()).unwrap(), None);
+	}
+
+	#[test]
+	fn metadata_only_reads_fail_on_truncated_description() {
+		let save = format!("{}\r\n3\r\nOnly line\r\n", fixture::HEADER);
+		let error = read_description_only(save.as_bytes()).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+		let error = read_brick_count_only(save.as_bytes()).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+	}
+
+	/// A save whose bricks never end.
+	struct EndlessSave {
+		head: Cursor<Vec<u8>>,
+		line: &'static [u8],
+		position: usize,
+	}
+
+	impl Read for EndlessSave {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			let read = self.head.read(buf)?;
+			if read > 0 {
+				return Ok(read);
+			}
+			for byte in buf.iter_mut() {
+				*byte = self.line[self.position];
+				self.position = (self.position + 1) % self.line.len();
+			}
+			Ok(buf.len())
+		}
+	}
+
+	#[test]
+	fn strict_limits_stop_at_the_brick_limit() {
+		let source = EndlessSave {
+			head: Cursor::new(fixture::save(&[]).into_bytes()),
+			line: b"1x1\" 0 0 0.2 0 0 0  0 0 1 1 1\r\n",
+			position: 0,
+		};
+		let reader = ReaderBuilder::new(io::BufReader::new(source))
+			.read_limits(ReadLimits::strict())
+			.build()
+			.unwrap();
+
+		let mut bricks = 0;
+		let mut errors = Vec::new();
+		for brick in reader {
+			match brick {
+				Ok(_) => bricks += 1,
+				Err(e) => errors.push(e),
+			}
+		}
+		assert_eq!(bricks, 1_000_000);
+		assert_eq!(errors.len(), 1);
+		assert_eq!(errors[0].kind(), io::ErrorKind::InvalidData);
+		assert_eq!(errors[0].to_string(), "exceeded max_brick_count of 1000000");
+		let limit = errors[0].get_ref().unwrap().downcast_ref::<LimitExceeded>();
+		assert_eq!(
+			limit,
+			Some(&LimitExceeded {
+				limit_name: "max_brick_count",
+				value: 1_000_000
+			})
+		);
+	}
+
+	#[test]
+	fn description_lines_split_the_description() {
+		let save = fixture::save_with_description(&["First", "", "Third"], &[]);
+		let reader = Reader::from_str(&save).unwrap();
+		let lines: Vec<&str> = reader.description_lines().collect();
+		assert_eq!(lines, vec!["First", "", "Third"]);
+	}
+}
@@ -0,0 +1,127 @@
+use crate::{Brick, Reader};
+use std::io::{self, prelude::*};
+use std::ops::Deref;
+
+impl<R: BufRead> Reader<R> {
+	/// Read at most `n` more results, counting errors as well as bricks, so
+	/// a corrupt source can't make it read to the end.
+	///
+	/// To collect the extra data of a brick, the reader looks at the line
+	/// after it, so the source may be read one line past the last brick
+	/// returned. The reader can be recovered with
+	/// [`TakeBricks::into_inner`](struct.TakeBricks.html#method.into_inner)
+	/// to continue reading.
+	///
+	/// ```rust,no_run
+	/// # use std::{fs::File, io::BufReader};
+	/// # fn main() -> std::io::Result<()> {
+	/// let reader = bl_save::Reader::new(BufReader::new(File::open("House.bls")?))?;
+	///
+	/// for brick in reader.take_bricks(10) {
+	///     println!("{}", brick?);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn take_bricks(self, n: usize) -> TakeBricks<R> {
+		TakeBricks {
+			reader: self,
+			remaining: n,
+		}
+	}
+}
+
+/// A reader that stops after a number of bricks, created by
+/// [`Reader::take_bricks`](struct.Reader.html#method.take_bricks).
+///
+/// Metadata accessors of the inner [`Reader`](struct.Reader.html) are
+/// available through `Deref`.
+pub struct TakeBricks<R: BufRead> {
+	reader: Reader<R>,
+	remaining: usize,
+}
+
+impl<R: BufRead> TakeBricks<R> {
+	/// Unwrap the inner reader, which continues after the last brick read.
+	pub fn into_inner(self) -> Reader<R> {
+		self.reader
+	}
+}
+
+impl<R: BufRead> Deref for TakeBricks<R> {
+	type Target = Reader<R>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.reader
+	}
+}
+
+impl<R: BufRead> Iterator for TakeBricks<R> {
+	type Item = io::Result<Brick>;
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		if self.remaining == 0 {
+			return (0, Some(0));
+		}
+		match self.reader.size_hint() {
+			(lower, Some(upper)) => (lower.min(self.remaining), Some(upper.min(self.remaining))),
+			(lower, None) => (lower.min(self.remaining), None),
+		}
+	}
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		self.reader.next()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{fixture, Reader};
+
+	#[test]
+	fn inner_reader_continues_after_taken_bricks() {
+		let save = fixture::numbered(100);
+		let mut taken = Reader::from_str(&save).unwrap().take_bricks(5);
+		assert_eq!(taken.size_hint(), (0, Some(5)));
+
+		let names: Vec<String> = taken
+			.by_ref()
+			.map(|brick| brick.unwrap().base.ui_name)
+			.collect();
+		assert_eq!(names, vec!["0", "1", "2", "3", "4"]);
+		assert!(taken.next().is_none());
+
+		let rest: Vec<_> = taken.into_inner().map(Result::unwrap).collect();
+		assert_eq!(rest.len(), 95);
+		assert_eq!(rest[0].base.ui_name, "5");
+	}
+
+	#[test]
+	fn errors_count_toward_n() {
+		let save = fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.2)),
+			"No quote",
+			&fixture::line("B", (0.0, 0.0, 0.4)),
+			&fixture::line("C", (0.0, 0.0, 0.6)),
+		]);
+		let results: Vec<_> = Reader::from_str(&save).unwrap().take_bricks(2).collect();
+		assert_eq!(results.len(), 2);
+		assert_eq!(results[0].as_ref().unwrap().base.ui_name, "A");
+		assert!(results[1].is_err());
+	}
+
+	#[test]
+	fn corrupt_source_stops_after_n_errors() {
+		let lines = vec!["No quote"; 100];
+		let save = fixture::save(&lines);
+		let mut taken = Reader::from_str(&save).unwrap().take_bricks(3);
+
+		assert_eq!(taken.by_ref().filter(Result::is_err).count(), 3);
+		assert_eq!(taken.line_count(), 71);
+		assert_eq!(taken.into_inner().count(), 97);
+	}
+}
@@ -0,0 +1,888 @@
+//! Operations that rewrite or reorganize collections of bricks.
+
+use crate::{Brick, Color, Colors};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+
+type DuplicateKey<'a> = (&'a str, (i32, i32, i32), u8);
+
+/// Bricks are considered duplicates when they share a `ui_name`, `angle`
+/// and position. Positions are snapped to the brick grid before comparison
+/// so tiny float differences don't hide duplicates.
+fn duplicate_key(brick: &Brick) -> DuplicateKey<'_> {
+	(
+		&brick.base.ui_name,
+		brick.base.grid_position(),
+		brick.base.angle,
+	)
+}
+
+/// Remove duplicate bricks, keeping the first occurrence of each.
+pub fn deduplicate(bricks: Vec<Brick>) -> Vec<Brick> {
+	let mut seen = HashSet::new();
+	let keep: Vec<bool> = bricks
+		.iter()
+		.map(|brick| seen.insert(duplicate_key(brick)))
+		.collect();
+
+	bricks
+		.into_iter()
+		.zip(keep)
+		.filter_map(|(brick, keep)| if keep { Some(brick) } else { None })
+		.collect()
+}
+
+/// Lazily remove duplicate bricks that directly follow each other,
+/// keeping the first of each run.
+///
+/// Only adjacent duplicates are detected, so the input should be sorted.
+/// Use [`deduplicate`](fn.deduplicate.html) for unsorted bricks.
+pub fn deduplicate_sorted(bricks: impl Iterator<Item = Brick>) -> impl Iterator<Item = Brick> {
+	let mut previous: Option<(String, (i32, i32, i32), u8)> = None;
+
+	bricks.filter(move |brick| {
+		let (ui_name, position, angle) = duplicate_key(brick);
+		if let Some(previous) = &previous {
+			if previous.0 == ui_name && previous.1 == position && previous.2 == angle {
+				return false;
+			}
+		}
+		previous = Some((String::from(ui_name), position, angle));
+		true
+	})
+}
+
+/// Find duplicate bricks without removing them.
+///
+/// Returns `(first, duplicate)` index pairs, where `first` is the index of
+/// the first occurrence and `duplicate` is the index of a later copy.
+pub fn find_duplicates(bricks: &[Brick]) -> Vec<(usize, usize)> {
+	let mut first_seen = HashMap::new();
+	let mut duplicates = Vec::new();
+
+	for (index, brick) in bricks.iter().enumerate() {
+		let first = *first_seen.entry(duplicate_key(brick)).or_insert(index);
+		if first != index {
+			duplicates.push((first, index));
+		}
+	}
+
+	duplicates
+}
+
+/// Lazily rewrite the color index of each brick, where `map[old] = new`.
+///
+/// Fails if any entry in `map` is not a valid color index.
+/// Bricks with an out-of-range color index are left unchanged.
+pub fn remap_colors(
+	bricks: impl Iterator<Item = Brick>,
+	map: &[u8; 64],
+) -> io::Result<impl Iterator<Item = Brick>> {
+	if map.iter().any(|&index| index >= 64) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"Color map contains an index outside 0 through 63",
+		));
+	}
+
+	let map = *map;
+	Ok(bricks.map(move |mut brick| {
+		if let Some(&index) = map.get(usize::from(brick.base.color_index)) {
+			brick.base.color_index = index;
+		}
+		brick
+	}))
+}
+
+/// Build a map for [`remap_colors`](fn.remap_colors.html) that sends each
+/// color in `from` to the nearest color in `to`, by Euclidean distance in
+/// RGBA space.
+pub fn build_remap(from: &Colors, to: &Colors) -> [u8; 64] {
+	let mut map = [0; 64];
+
+	for (entry, color) in map.iter_mut().zip(from.iter()) {
+		let mut nearest = 0;
+		let mut nearest_distance = f32::INFINITY;
+
+		for (index, candidate) in to.iter().enumerate() {
+			let distance = color_distance_squared(*color, *candidate);
+			if distance < nearest_distance {
+				nearest = index;
+				nearest_distance = distance;
+			}
+		}
+
+		*entry = nearest as u8;
+	}
+
+	map
+}
+
+/// Reorder the slots of a colorset without changing the color of any
+/// brick, where `new_order[new] = old`.
+///
+/// Returns the bricks with updated color indices and the reordered
+/// colorset. Fails if `new_order` is not a permutation of `0` through `63`.
+/// Bricks with an out-of-range color index are left unchanged.
+pub fn reorder_colorset(
+	bricks: Vec<Brick>,
+	colors: &Colors,
+	new_order: &[u8; 64],
+) -> io::Result<(Vec<Brick>, Colors)> {
+	let mut inverse = [64; 64];
+	for (new, &old) in new_order.iter().enumerate() {
+		match inverse.get_mut(usize::from(old)) {
+			Some(slot) if *slot == 64 => *slot = new as u8,
+			_ => {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"Color order is not a permutation of 0 through 63",
+				))
+			}
+		}
+	}
+
+	let mut reordered = *colors;
+	for (slot, &old) in reordered.iter_mut().zip(new_order.iter()) {
+		*slot = colors[usize::from(old)];
+	}
+
+	let bricks = remap_colors(bricks.into_iter(), &inverse)?.collect();
+	Ok((bricks, reordered))
+}
+
+fn color_distance_squared(a: Color, b: Color) -> f32 {
+	(a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2) + (a.3 - b.3).powi(2)
+}
+
+/// The key bricks are ordered by for ground-up processing: the position
+/// as `(z, y, x)`.
+pub fn z_order_key(brick: &Brick) -> (f32, f32, f32) {
+	let (x, y, z) = brick.base.position;
+	(z, y, x)
+}
+
+fn cmp_z_order(a: &Brick, b: &Brick) -> Ordering {
+	let (a, b) = (z_order_key(a), z_order_key(b));
+	a.0.total_cmp(&b.0)
+		.then(a.1.total_cmp(&b.1))
+		.then(a.2.total_cmp(&b.2))
+}
+
+fn cmp_type(a: &Brick, b: &Brick) -> Ordering {
+	a.base
+		.ui_name
+		.cmp(&b.base.ui_name)
+		.then_with(|| cmp_z_order(a, b))
+}
+
+/// Sort bricks from the ground up by `(z, y, x)`.
+///
+/// Uses `f32::total_cmp`, so `NaN` positions don't cause a panic.
+pub fn sort_by_position(bricks: &mut [Brick]) {
+	bricks.sort_by(cmp_z_order);
+}
+
+/// Like [`sort_by_position`](fn.sort_by_position.html), but doesn't
+/// preserve the order of bricks at equal positions.
+pub fn sort_by_position_unstable(bricks: &mut [Brick]) {
+	bricks.sort_unstable_by(cmp_z_order);
+}
+
+/// Sort bricks by `ui_name`, then by position as in
+/// [`sort_by_position`](fn.sort_by_position.html).
+pub fn sort_by_type(bricks: &mut [Brick]) {
+	bricks.sort_by(cmp_type);
+}
+
+/// Like [`sort_by_type`](fn.sort_by_type.html), but doesn't preserve the
+/// order of equal bricks.
+pub fn sort_by_type_unstable(bricks: &mut [Brick]) {
+	bricks.sort_unstable_by(cmp_type);
+}
+
+/// Group bricks into horizontal layers `layer_height` units thick,
+/// starting from the lowest brick. Layers are ordered from the bottom up
+/// and empty layers are omitted. Bricks keep their relative order.
+///
+/// This is [`layers`](fn.layers.html) without the empty layers, so bricks
+/// with a non-finite Z position are in the bottom layer.
+///
+/// # Panics
+///
+/// Panics if `layer_height` is not positive.
+pub fn partition_by_layer(bricks: Vec<Brick>, layer_height: f32) -> Vec<Vec<Brick>> {
+	layers(bricks, layer_height)
+		.into_iter()
+		.filter(|layer| !layer.is_empty())
+		.collect()
+}
+
+/// Pair each brick with the number of its horizontal layer, where layers
+/// are `layer_height` units thick and layer `0` starts at the lowest brick.
+/// Bricks with a non-finite Z position are put in layer `0`.
+///
+/// # Panics
+///
+/// Panics if `layer_height` is not positive.
+pub fn assign_layer_ids(bricks: Vec<Brick>, layer_height: f32) -> Vec<(u32, Brick)> {
+	assert!(layer_height > 0.0, "layer height must be positive");
+
+	let bottom = lowest_z(&bricks);
+	bricks
+		.into_iter()
+		.map(|brick| {
+			let z = brick.base.position.2;
+			let layer = if z.is_finite() {
+				((z - bottom) / layer_height).floor() as u32
+			} else {
+				0
+			};
+			(layer, brick)
+		})
+		.collect()
+}
+
+/// Group bricks by their [layer ID](fn.assign_layer_ids.html), so the
+/// bricks of layer `n` are at index `n`. Unlike
+/// [`partition_by_layer`](fn.partition_by_layer.html), empty layers
+/// between the lowest and highest brick are included, so a brick far above
+/// the rest allocates every layer in between. Bricks keep their relative
+/// order.
+///
+/// # Panics
+///
+/// Panics if `layer_height` is not positive.
+pub fn layers(bricks: Vec<Brick>, layer_height: f32) -> Vec<Vec<Brick>> {
+	let mut layers: Vec<Vec<Brick>> = Vec::new();
+	for (layer, brick) in assign_layer_ids(bricks, layer_height) {
+		let layer = layer as usize;
+		if layer >= layers.len() {
+			layers.resize_with(layer + 1, Vec::new);
+		}
+		layers[layer].push(brick);
+	}
+	layers
+}
+
+fn lowest_z(bricks: &[Brick]) -> f32 {
+	bricks
+		.iter()
+		.map(|brick| brick.base.position.2)
+		.filter(|z| z.is_finite())
+		.fold(f32::INFINITY, f32::min)
+}
+
+/// An axis of the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+	X,
+	Y,
+	Z,
+}
+
+/// Lazily mirror bricks across the plane through the origin perpendicular
+/// to `axis`.
+///
+/// See [`mirror_around`](fn.mirror_around.html).
+pub fn mirror(bricks: impl Iterator<Item = Brick>, axis: Axis) -> impl Iterator<Item = Brick> {
+	mirror_around(bricks, axis, 0.0)
+}
+
+/// Lazily mirror bricks across the plane perpendicular to `axis` at
+/// `origin` along that axis.
+///
+/// The position along `axis` is reflected. Mirroring across X swaps angles
+/// `1` and `3`, and mirroring across Y swaps angles `0` and `2`; mirroring
+/// across Z leaves the angle unchanged. Only the placement is mirrored, so
+/// asymmetric bricks such as ramps keep their own shape. Mirroring twice
+/// restores the original bricks, up to float rounding for a non-zero
+/// `origin`.
+pub fn mirror_around(
+	bricks: impl Iterator<Item = Brick>,
+	axis: Axis,
+	origin: f32,
+) -> impl Iterator<Item = Brick> {
+	bricks.map(move |mut brick| {
+		let base = &mut brick.base;
+		match axis {
+			Axis::X => {
+				base.position.0 = 2.0 * origin - base.position.0;
+				base.angle = match base.angle {
+					1 => 3,
+					3 => 1,
+					angle => angle,
+				};
+			}
+			Axis::Y => {
+				base.position.1 = 2.0 * origin - base.position.1;
+				base.angle = match base.angle {
+					0 => 2,
+					2 => 0,
+					angle => angle,
+				};
+			}
+			Axis::Z => base.position.2 = 2.0 * origin - base.position.2,
+		}
+		brick
+	})
+}
+
+/// Shift bricks so the minimum X, Y and Z across all positions is at the
+/// origin. Does nothing for an empty slice.
+pub fn normalize_position(bricks: &mut [Brick]) {
+	if let Some(min) = min_position(bricks) {
+		translate(bricks, min);
+	}
+}
+
+/// Shift bricks so the centroid of their positions is at the origin.
+/// Does nothing for an empty slice.
+pub fn center(bricks: &mut [Brick]) {
+	if bricks.is_empty() {
+		return;
+	}
+
+	let count = bricks.len() as f64;
+	let sum = bricks.iter().fold((0.0, 0.0, 0.0), |sum, brick| {
+		let position = brick.base.position;
+		(
+			sum.0 + f64::from(position.0),
+			sum.1 + f64::from(position.1),
+			sum.2 + f64::from(position.2),
+		)
+	});
+	let centroid = (
+		(sum.0 / count) as f32,
+		(sum.1 / count) as f32,
+		(sum.2 / count) as f32,
+	);
+
+	translate(bricks, centroid);
+}
+
+/// Like [`normalize_position`](fn.normalize_position.html), but for an
+/// iterator.
+///
+/// The minimum can only be known after seeing every brick, so all bricks
+/// are collected into memory before the first one is returned.
+pub fn normalized(bricks: impl Iterator<Item = Brick>) -> impl Iterator<Item = Brick> {
+	let mut bricks: Vec<Brick> = bricks.collect();
+	normalize_position(&mut bricks);
+	bricks.into_iter()
+}
+
+fn min_position(bricks: &[Brick]) -> Option<(f32, f32, f32)> {
+	bricks.iter().map(|brick| brick.base.position).reduce(|min, position| {
+		(
+			min.0.min(position.0),
+			min.1.min(position.1),
+			min.2.min(position.2),
+		)
+	})
+}
+
+fn translate(bricks: &mut [Brick], offset: (f32, f32, f32)) {
+	for brick in bricks {
+		let position = &mut brick.base.position;
+		position.0 -= offset.0;
+		position.1 -= offset.1;
+		position.2 -= offset.2;
+	}
+}
+
+/// Close the gaps in a build along `gravity`, dropping bricks toward the
+/// negative end of that axis.
+///
+/// The build is cut into layers `layer_height` units thick, starting from
+/// the lowest brick, and each layer drops onto the occupied layer below it.
+/// Layers containing a baseplate are anchored: they don't move, and layers
+/// above them only drop as far as the anchor. Bricks with a non-finite
+/// position don't move either. Bricks keep their relative order.
+///
+/// Save files don't record brick sizes, so what counts as a gap can't be
+/// derived from the bricks alone; that is what `layer_height` is for. Only
+/// positions move, never the visual geometry. Choose a `layer_height` no
+/// larger than the thinnest brick that may stand on another, or stacked
+/// bricks may end up overlapping.
+///
+/// # Panics
+///
+/// Panics if `layer_height` is not positive.
+pub fn pack(mut bricks: Vec<Brick>, gravity: Axis, layer_height: f32) -> Vec<Brick> {
+	assert!(layer_height > 0.0, "layer height must be positive");
+
+	let bottom = bricks
+		.iter()
+		.map(|brick| along(&brick.base.position, gravity))
+		.filter(|c| c.is_finite())
+		.fold(f32::INFINITY, f32::min);
+	let layer_of = |c: f32| ((c - bottom) / layer_height).floor() as u64;
+
+	// Occupied layers, and whether each is anchored by a baseplate.
+	let mut occupied: BTreeMap<u64, bool> = BTreeMap::new();
+	for brick in &bricks {
+		let c = along(&brick.base.position, gravity);
+		if c.is_finite() {
+			*occupied.entry(layer_of(c)).or_default() |= brick.base.is_baseplate;
+		}
+	}
+
+	let mut next_free = 0;
+	let shifts: HashMap<u64, u64> = occupied
+		.into_iter()
+		.map(|(layer, anchored)| {
+			let target = if anchored { layer } else { next_free };
+			next_free = target + 1;
+			(layer, layer - target)
+		})
+		.collect();
+
+	for brick in &mut bricks {
+		let c = along(&brick.base.position, gravity);
+		if c.is_finite() {
+			let shift = shifts[&layer_of(c)] as f32 * layer_height;
+			*along_mut(&mut brick.base.position, gravity) -= shift;
+		}
+	}
+
+	bricks
+}
+
+fn along(position: &(f32, f32, f32), axis: Axis) -> f32 {
+	match axis {
+		Axis::X => position.0,
+		Axis::Y => position.1,
+		Axis::Z => position.2,
+	}
+}
+
+fn along_mut(position: &mut (f32, f32, f32), axis: Axis) -> &mut f32 {
+	match axis {
+		Axis::X => &mut position.0,
+		Axis::Y => &mut position.1,
+		Axis::Z => &mut position.2,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture::{self, brick};
+	use crate::Reader;
+
+	fn names(layers: &[Vec<Brick>]) -> Vec<Vec<&str>> {
+		layers
+			.iter()
+			.map(|layer| layer.iter().map(|b| b.base.ui_name.as_str()).collect())
+			.collect()
+	}
+
+	#[test]
+	fn layers_hold_bricks_by_z() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 0.0)),
+			&fixture::line("B", (0.0, 0.0, 1.0)),
+			&fixture::line("C", (0.0, 0.0, 2.0)),
+			&fixture::line("D", (1.0, 0.0, 1.0)),
+		]));
+		let layers = layers(bricks, 1.0);
+		assert_eq!(names(&layers), vec![vec!["A"], vec!["B", "D"], vec!["C"]]);
+	}
+
+	#[test]
+	fn layers_include_empty_layers() {
+		let bricks = vec![brick("A", (0.0, 0.0, 0.5)), brick("B", (0.0, 0.0, 3.5))];
+		let layers = layers(bricks, 1.0);
+		assert_eq!(names(&layers), vec![vec!["A"], vec![], vec![], vec!["B"]]);
+	}
+
+	#[test]
+	fn non_finite_z_is_in_the_bottom_layer() {
+		let bricks = || {
+			vec![
+				brick("A", (0.0, 0.0, 1.0)),
+				brick("B", (0.0, 0.0, f32::NAN)),
+				brick("C", (0.0, 0.0, 2.0)),
+				brick("D", (0.0, 0.0, f32::INFINITY)),
+				brick("E", (0.0, 0.0, f32::NEG_INFINITY)),
+			]
+		};
+		let ids: Vec<u32> = assign_layer_ids(bricks(), 1.0).iter().map(|(id, _)| *id).collect();
+		assert_eq!(ids, vec![0, 0, 1, 0, 0]);
+
+		let expected = vec![vec!["A", "B", "D", "E"], vec!["C"]];
+		assert_eq!(names(&layers(bricks(), 1.0)), expected);
+		assert_eq!(names(&partition_by_layer(bricks(), 1.0)), expected);
+	}
+
+	fn positions(bricks: &[Brick]) -> Vec<(&str, (f32, f32, f32))> {
+		bricks
+			.iter()
+			.map(|brick| (brick.base.ui_name.as_str(), brick.base.position))
+			.collect()
+	}
+
+	#[test]
+	fn pack_collapses_gap() {
+		let bricks = vec![
+			brick("A", (0.0, 0.0, 0.0)),
+			brick("B", (0.0, 0.0, 1.0)),
+			brick("C", (0.0, 0.0, 3.0)),
+			brick("D", (1.0, 0.0, 3.0)),
+			brick("E", (0.0, 0.0, 4.0)),
+		];
+		assert_eq!(
+			positions(&pack(bricks, Axis::Z, 1.0)),
+			vec![
+				("A", (0.0, 0.0, 0.0)),
+				("B", (0.0, 0.0, 1.0)),
+				("C", (0.0, 0.0, 2.0)),
+				("D", (1.0, 0.0, 2.0)),
+				("E", (0.0, 0.0, 3.0)),
+			]
+		);
+	}
+
+	#[test]
+	fn pack_keeps_baseplate_layers_in_place() {
+		let mut bricks = vec![
+			brick("A", (0.0, 0.0, 0.0)),
+			brick("Base", (0.0, 0.0, 5.0)),
+			brick("B", (0.0, 0.0, 5.0)),
+			brick("C", (0.0, 0.0, 8.0)),
+		];
+		bricks[1].base.is_baseplate = true;
+		assert_eq!(
+			positions(&pack(bricks, Axis::Z, 1.0)),
+			vec![
+				("A", (0.0, 0.0, 0.0)),
+				("Base", (0.0, 0.0, 5.0)),
+				("B", (0.0, 0.0, 5.0)),
+				("C", (0.0, 0.0, 6.0)),
+			]
+		);
+	}
+
+	#[test]
+	fn pack_along_other_axes() {
+		let bricks = vec![
+			brick("A", (-2.0, 0.0, 7.0)),
+			brick("B", (4.0, 0.0, 0.0)),
+			brick("C", (0.0, 0.0, 3.0)),
+		];
+		assert_eq!(
+			positions(&pack(bricks, Axis::X, 2.0)),
+			vec![
+				("A", (-2.0, 0.0, 7.0)),
+				("B", (2.0, 0.0, 0.0)),
+				("C", (0.0, 0.0, 3.0)),
+			]
+		);
+	}
+
+	#[test]
+	fn pack_leaves_non_finite_positions() {
+		let bricks = vec![brick("A", (0.0, 0.0, 4.0)), brick("B", (0.0, 0.0, f32::NAN))];
+		let packed = pack(bricks, Axis::Z, 1.0);
+		assert_eq!(packed[0].base.position.2, 4.0);
+		assert!(packed[1].base.position.2.is_nan());
+	}
+
+	#[test]
+	fn deduplicate_keeps_first_of_identical_bricks() {
+		let save = fixture::save(&[
+			&fixture::line("1x1", (1.0, 0.0, 0.2)),
+			&fixture::line("1x1", (1.0, 0.0, 0.2)),
+			&fixture::line("2x2", (5.0, 0.0, 0.2)),
+			&fixture::line("1x1", (1.0, 0.0, 0.2)),
+		]);
+		let bricks = fixture::read(&save);
+
+		assert_eq!(find_duplicates(&bricks), vec![(0, 1), (0, 3)]);
+		assert_eq!(
+			positions(&deduplicate(bricks)),
+			vec![("1x1", (1.0, 0.0, 0.2)), ("2x2", (5.0, 0.0, 0.2))]
+		);
+	}
+
+	#[test]
+	fn duplicates_ignore_float_noise_but_not_angle() {
+		let mut bricks = vec![
+			brick("1x1", (1.0, 0.0, 0.2)),
+			brick("1x1", (1.000_01, 0.0, 0.2)),
+			brick("1x1", (1.0, 0.0, 0.2)),
+		];
+		bricks[2].base.angle = 1;
+		assert_eq!(deduplicate(bricks).len(), 2);
+	}
+
+	#[test]
+	fn deduplicate_sorted_removes_runs() {
+		let bricks = vec![
+			brick("1x1", (1.0, 0.0, 0.2)),
+			brick("1x1", (1.0, 0.0, 0.2)),
+			brick("1x1", (1.0, 0.0, 0.2)),
+			brick("2x2", (1.0, 0.0, 0.2)),
+			brick("1x1", (1.0, 0.0, 0.2)),
+		];
+		let names: Vec<String> = deduplicate_sorted(bricks.into_iter())
+			.map(|brick| brick.base.ui_name)
+			.collect();
+		assert_eq!(names, vec!["1x1", "2x2", "1x1"]);
+	}
+
+	fn identity() -> [u8; 64] {
+		let mut map = [0; 64];
+		for (index, entry) in map.iter_mut().enumerate() {
+			*entry = index as u8;
+		}
+		map
+	}
+
+	fn with_colors(indices: &[u8]) -> Vec<Brick> {
+		indices
+			.iter()
+			.map(|&index| {
+				let mut brick = brick("1x1", (0.0, 0.0, 0.2));
+				brick.base.color_index = index;
+				brick
+			})
+			.collect()
+	}
+
+	fn color_indices(bricks: impl IntoIterator<Item = Brick>) -> Vec<u8> {
+		bricks.into_iter().map(|brick| brick.base.color_index).collect()
+	}
+
+	#[test]
+	fn remap_identity_changes_nothing() {
+		let bricks = with_colors(&[0, 1, 63, 64]);
+		let remapped = remap_colors(bricks.into_iter(), &identity()).unwrap();
+		assert_eq!(color_indices(remapped), vec![0, 1, 63, 64]);
+	}
+
+	#[test]
+	fn remap_swaps_indices() {
+		let mut map = identity();
+		map.swap(0, 1);
+		let remapped = remap_colors(with_colors(&[0, 1, 2]).into_iter(), &map).unwrap();
+		assert_eq!(color_indices(remapped), vec![1, 0, 2]);
+	}
+
+	#[test]
+	fn remap_rejects_invalid_map() {
+		let mut map = identity();
+		map[5] = 64;
+		assert!(remap_colors(with_colors(&[0]).into_iter(), &map).is_err());
+	}
+
+	#[test]
+	fn build_remap_finds_nearest_colors() {
+		let mut from = [(0.0, 0.0, 0.0, 1.0); 64];
+		from[1] = (0.9, 0.1, 0.1, 1.0);
+		let mut to = [(1.0, 1.0, 1.0, 1.0); 64];
+		to[7] = (0.0, 0.0, 0.0, 1.0);
+		to[9] = (1.0, 0.0, 0.0, 1.0);
+
+		let map = build_remap(&from, &to);
+		assert_eq!(map[0], 7);
+		assert_eq!(map[1], 9);
+		assert_eq!(build_remap(&to, &to)[9], 9);
+	}
+
+	fn with_angle(position: (f32, f32, f32), angle: u8) -> Brick {
+		let mut brick = brick("Ramp", position);
+		brick.base.angle = angle;
+		brick
+	}
+
+	#[test]
+	fn mirror_reflects_angles() {
+		let angles = |bricks: Vec<Brick>| -> Vec<u8> { bricks.iter().map(|b| b.base.angle).collect() };
+		let bricks: Vec<Brick> = (0..4).map(|angle| with_angle((1.0, 2.0, 3.0), angle)).collect();
+
+		assert_eq!(angles(mirror(bricks.clone().into_iter(), Axis::X).collect()), vec![0, 3, 2, 1]);
+		assert_eq!(angles(mirror(bricks.clone().into_iter(), Axis::Y).collect()), vec![2, 1, 0, 3]);
+		assert_eq!(angles(mirror(bricks.into_iter(), Axis::Z).collect()), vec![0, 1, 2, 3]);
+	}
+
+	#[test]
+	fn mirror_twice_restores_bricks() {
+		let bricks: Vec<Brick> = (0..4)
+			.map(|angle| with_angle((1.5, -2.25, 3.0), angle))
+			.collect();
+		for &axis in &[Axis::X, Axis::Y, Axis::Z] {
+			let once: Vec<Brick> = mirror(bricks.clone().into_iter(), axis).collect();
+			assert_ne!(once[0].base, bricks[0].base);
+			let twice: Vec<Brick> = mirror(once.into_iter(), axis).collect();
+			for (twice, original) in twice.iter().zip(&bricks) {
+				assert_eq!(twice.base, original.base);
+			}
+		}
+	}
+
+	#[test]
+	fn mirror_around_origin() {
+		let bricks = vec![brick("A", (1.0, 2.0, 3.0))];
+		let mirrored: Vec<Brick> = mirror_around(bricks.into_iter(), Axis::X, 5.0).collect();
+		assert_eq!(mirrored[0].base.position, (9.0, 2.0, 3.0));
+	}
+
+	#[test]
+	fn normalize_moves_minimum_to_origin() {
+		let save = fixture::save(&[
+			&fixture::line("A", (6.0, 7.0, 5.5)),
+			&fixture::line("B", (5.0, 5.0, 5.0)),
+			&fixture::line("C", (10.0, 5.5, 8.0)),
+		]);
+		let expected = vec![
+			("A", (1.0, 2.0, 0.5)),
+			("B", (0.0, 0.0, 0.0)),
+			("C", (5.0, 0.5, 3.0)),
+		];
+
+		let mut bricks = fixture::read(&save);
+		normalize_position(&mut bricks);
+		assert_eq!(positions(&bricks), expected);
+
+		let bricks: Vec<Brick> = normalized(fixture::read(&save).into_iter()).collect();
+		assert_eq!(positions(&bricks), expected);
+
+		let mut empty: Vec<Brick> = Vec::new();
+		normalize_position(&mut empty);
+		assert!(normalized(empty.into_iter()).next().is_none());
+	}
+
+	#[test]
+	fn center_moves_centroid_to_origin() {
+		let mut bricks = vec![
+			brick("A", (0.0, 0.0, 0.0)),
+			brick("B", (2.0, 4.0, 6.0)),
+			brick("C", (4.0, 8.0, 12.0)),
+		];
+		center(&mut bricks);
+		assert_eq!(
+			positions(&bricks),
+			vec![
+				("A", (-2.0, -4.0, -6.0)),
+				("B", (0.0, 0.0, 0.0)),
+				("C", (2.0, 4.0, 6.0)),
+			]
+		);
+	}
+
+	#[test]
+	fn reorder_swaps_slots_and_bricks() {
+		let save = fixture::save(&[
+			"1x1\" 0 0 0.2 0 0 0  0 0 1 1 1",
+			"1x1\" 1 0 0.2 0 0 1  0 0 1 1 1",
+			"1x1\" 2 0 0.2 0 0 2  0 0 1 1 1",
+		]);
+		let reader = Reader::from_str(&save).unwrap();
+		let mut colors = *reader.colors();
+		colors[0] = (1.0, 0.0, 0.0, 1.0);
+		colors[1] = (0.0, 1.0, 0.0, 1.0);
+		let bricks: Vec<Brick> = reader.map(Result::unwrap).collect();
+
+		let mut order = identity();
+		order.swap(0, 1);
+		let (bricks, reordered) = reorder_colorset(bricks, &colors, &order).unwrap();
+		assert_eq!(color_indices(bricks), vec![1, 0, 2]);
+		assert_eq!(reordered[1], colors[0]);
+		assert_eq!(reordered[0], colors[1]);
+		assert_eq!(reordered[2..], colors[2..]);
+	}
+
+	#[test]
+	fn reorder_rejects_invalid_permutations() {
+		let colors = [(0.5, 0.5, 0.5, 1.0); 64];
+
+		let mut duplicate = identity();
+		duplicate[1] = 0;
+		let mut out_of_range = identity();
+		out_of_range[63] = 64;
+
+		for order in &[duplicate, out_of_range] {
+			let error = reorder_colorset(with_colors(&[0]), &colors, order).unwrap_err();
+			assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+		}
+	}
+
+	#[test]
+	fn partition_into_three_layers() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("A", (0.0, 0.0, 4.5)),
+			&fixture::line("B", (0.0, 0.0, 5.0)),
+			&fixture::line("C", (1.0, 0.0, 6.5)),
+			&fixture::line("D", (1.0, 0.0, 4.0)),
+			&fixture::line("E", (2.0, 0.0, 5.5)),
+		]));
+		let layers = partition_by_layer(bricks, 1.0);
+		assert_eq!(names(&layers), vec![vec!["A", "D"], vec!["B", "E"], vec!["C"]]);
+	}
+
+	#[test]
+	fn sort_orders_by_z_then_y_then_x() {
+		let bricks = fixture::read(&fixture::save(&[
+			&fixture::line("A", (1.0, 0.0, 0.6)),
+			&fixture::line("B", (0.0, 1.0, 0.2)),
+			&fixture::line("C", (1.0, 0.0, 0.2)),
+			&fixture::line("D", (0.0, 0.0, 0.2)),
+		]));
+
+		let mut sorted = bricks.clone();
+		sort_by_position(&mut sorted);
+		assert_eq!(names(&[sorted]), vec![vec!["D", "C", "B", "A"]]);
+
+		let mut sorted = bricks;
+		sort_by_position_unstable(&mut sorted);
+		assert_eq!(names(&[sorted]), vec![vec!["D", "C", "B", "A"]]);
+		assert_eq!(z_order_key(&brick("A", (1.0, 2.0, 3.0))), (3.0, 2.0, 1.0));
+	}
+
+	#[test]
+	fn sort_handles_nan_positions() {
+		let bricks = vec![
+			brick("A", (0.0, 0.0, f32::NAN)),
+			brick("B", (0.0, 0.0, 1.0)),
+			brick("C", (0.0, 0.0, -f32::NAN)),
+			brick("D", (0.0, 0.0, f32::NEG_INFINITY)),
+		];
+
+		// `total_cmp` orders negative NaN first and positive NaN last.
+		let mut sorted = bricks.clone();
+		sort_by_position(&mut sorted);
+		assert_eq!(names(&[sorted]), vec![vec!["C", "D", "B", "A"]]);
+
+		let mut sorted = bricks;
+		sort_by_position_unstable(&mut sorted);
+		assert_eq!(names(&[sorted]), vec![vec!["C", "D", "B", "A"]]);
+	}
+
+	#[test]
+	fn sort_by_type_then_position() {
+		let bricks = vec![
+			brick("2x2", (0.0, 0.0, 0.6)),
+			brick("1x1", (0.0, 0.0, f32::NAN)),
+			brick("2x2", (0.0, 0.0, 0.2)),
+			brick("1x1", (0.0, 0.0, 0.2)),
+		];
+		let expected = vec!["1x1 0.2", "1x1 NaN", "2x2 0.2", "2x2 0.6"];
+		let key = |bricks: &[Brick]| -> Vec<String> {
+			bricks
+				.iter()
+				.map(|b| format!("{} {}", b.base.ui_name, b.base.position.2))
+				.collect()
+		};
+
+		let mut sorted = bricks.clone();
+		sort_by_type(&mut sorted);
+		assert_eq!(key(&sorted), expected);
+
+		let mut sorted = bricks;
+		sort_by_type_unstable(&mut sorted);
+		assert_eq!(key(&sorted), expected);
+	}
+}
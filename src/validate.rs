@@ -0,0 +1,289 @@
+use crate::{probe, BrickBase, ProbeResult, Reader};
+use std::{
+	fmt,
+	fs::File,
+	io::{self, prelude::*, BufReader},
+	path::Path,
+};
+
+/// A problem found while validating a save file.
+///
+/// Brick indices count from `0` in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+	/// The first line is not the standard save file header.
+	InvalidHeader(String),
+	/// The file has no `Linecount` line.
+	MissingLinecount,
+	/// The `Linecount` line doesn't match the number of bricks read.
+	LinecountMismatch { claimed: usize, actual: usize },
+	/// The angle is outside `0` through `3`.
+	InvalidAngle { brick: usize, angle: u8 },
+	/// The color index is outside `0` through `63`.
+	InvalidColorIndex { brick: usize, color_index: u8 },
+	/// The color effect is outside `0` through `6`.
+	InvalidColorFx { brick: usize, color_fx: u8 },
+	/// The shape effect is outside `0` through `2`.
+	InvalidShapeFx { brick: usize, shape_fx: u8 },
+	/// A position component is `NaN` or infinite.
+	NonFinitePosition { brick: usize },
+}
+
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Warning::InvalidHeader(line) => write!(f, "invalid header: {:?}", line),
+			Warning::MissingLinecount => write!(f, "missing Linecount line"),
+			Warning::LinecountMismatch { claimed, actual } => write!(
+				f,
+				"Linecount claims {} bricks but {} were read",
+				claimed, actual
+			),
+			Warning::InvalidAngle { brick, angle } => {
+				write!(f, "brick {}: invalid angle {}", brick, angle)
+			}
+			Warning::InvalidColorIndex { brick, color_index } => {
+				write!(f, "brick {}: invalid color index {}", brick, color_index)
+			}
+			Warning::InvalidColorFx { brick, color_fx } => {
+				write!(f, "brick {}: invalid color fx {}", brick, color_fx)
+			}
+			Warning::InvalidShapeFx { brick, shape_fx } => {
+				write!(f, "brick {}: invalid shape fx {}", brick, shape_fx)
+			}
+			Warning::NonFinitePosition { brick } => {
+				write!(f, "brick {}: position is not finite", brick)
+			}
+		}
+	}
+}
+
+/// The result of [`validate_bls_file`](fn.validate_bls_file.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationSummary {
+	/// The number of bricks read.
+	pub brick_count: usize,
+	/// Every problem found.
+	pub warnings: Vec<Warning>,
+	/// Whether no problems were found.
+	pub is_valid: bool,
+}
+
+/// The result of [`verify_linecount`](fn.verify_linecount.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinecountResult {
+	/// The brick count claimed by the `Linecount` line, if any.
+	pub claimed: Option<usize>,
+	/// The number of bricks actually read.
+	pub actual: usize,
+	/// Whether a claim exists and equals the actual count.
+	pub matches: bool,
+}
+
+impl LinecountResult {
+	/// The actual count minus the claimed count, if a claim exists.
+	/// Positive when there are more bricks than claimed.
+	pub fn discrepancy(&self) -> Option<i64> {
+		self.claimed
+			.map(|claimed| self.actual as i64 - claimed as i64)
+	}
+}
+
+/// Read all remaining bricks and compare the total number of bricks read
+/// with the brick count claimed by the file.
+///
+/// Returns the first error encountered while reading.
+pub fn verify_linecount<R: BufRead>(mut reader: Reader<R>) -> io::Result<LinecountResult> {
+	for brick in &mut reader {
+		brick?;
+	}
+
+	let claimed = reader.brick_count();
+	let actual = reader.bricks_yielded();
+	Ok(LinecountResult {
+		claimed,
+		actual,
+		matches: claimed == Some(actual),
+	})
+}
+
+/// Read an entire save file and check it for problems: the header, the
+/// claimed brick count, and the range of each brick's fields.
+///
+/// I/O and parse errors are returned as `Err`. Problems that Blockland
+/// would read past are reported in the summary's warnings instead.
+pub fn validate_bls_file(path: &Path) -> io::Result<ValidationSummary> {
+	let mut file = BufReader::new(File::open(path)?);
+	let mut warnings = Vec::new();
+
+	match probe(&mut file)? {
+		ProbeResult::Valid => {}
+		ProbeResult::InvalidHeader(line) => warnings.push(Warning::InvalidHeader(line)),
+		ProbeResult::Empty => warnings.push(Warning::InvalidHeader(String::new())),
+	}
+
+	let mut reader = Reader::after_header(file)?;
+	let mut brick_count = 0;
+
+	for brick in &mut reader {
+		validate_brick(brick_count, &brick?.base, &mut warnings);
+		brick_count += 1;
+	}
+
+	match reader.brick_count() {
+		None => warnings.push(Warning::MissingLinecount),
+		Some(claimed) if claimed != brick_count => warnings.push(Warning::LinecountMismatch {
+			claimed,
+			actual: brick_count,
+		}),
+		Some(_) => {}
+	}
+
+	Ok(ValidationSummary {
+		brick_count,
+		is_valid: warnings.is_empty(),
+		warnings,
+	})
+}
+
+fn validate_brick(index: usize, base: &BrickBase, warnings: &mut Vec<Warning>) {
+	if base.angle > 3 {
+		warnings.push(Warning::InvalidAngle {
+			brick: index,
+			angle: base.angle,
+		});
+	}
+	if base.color_index > 63 {
+		warnings.push(Warning::InvalidColorIndex {
+			brick: index,
+			color_index: base.color_index,
+		});
+	}
+	if base.color_fx > 6 {
+		warnings.push(Warning::InvalidColorFx {
+			brick: index,
+			color_fx: base.color_fx,
+		});
+	}
+	if base.shape_fx > 2 {
+		warnings.push(Warning::InvalidShapeFx {
+			brick: index,
+			shape_fx: base.shape_fx,
+		});
+	}
+	let (x, y, z) = base.position;
+	if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+		warnings.push(Warning::NonFinitePosition { brick: index });
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::fixture;
+
+	fn verify(save: &str) -> LinecountResult {
+		verify_linecount(Reader::from_str(save).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn linecount_correct() {
+		let result = verify(&fixture::numbered(3));
+		assert_eq!(
+			result,
+			LinecountResult {
+				claimed: Some(3),
+				actual: 3,
+				matches: true,
+			}
+		);
+		assert_eq!(result.discrepancy(), Some(0));
+	}
+
+	#[test]
+	fn linecount_wrong() {
+		let result = verify(&fixture::numbered(3).replace("Linecount 3", "Linecount 5"));
+		assert!(!result.matches);
+		assert_eq!(result.discrepancy(), Some(-2));
+	}
+
+	#[test]
+	fn linecount_missing() {
+		let result = verify(&fixture::numbered(3).replace("Linecount 3\r\n", ""));
+		assert_eq!(result.claimed, None);
+		assert_eq!(result.actual, 3);
+		assert!(!result.matches);
+		assert_eq!(result.discrepancy(), None);
+	}
+
+	#[test]
+	fn linecount_stops_at_error() {
+		let save = fixture::save(&[&fixture::line("A", (0.0, 0.0, 0.2)), "No quote"]);
+		assert!(verify_linecount(Reader::from_str(&save).unwrap()).is_err());
+	}
+
+	/// Validate a save written to a temporary file named after the test.
+	fn validate(name: &str, save: &str) -> ValidationSummary {
+		let path = std::env::temp_dir().join(format!("bl_save-{}-{}.bls", name, std::process::id()));
+		std::fs::write(&path, save).unwrap();
+		let summary = validate_bls_file(&path);
+		std::fs::remove_file(&path).unwrap();
+		summary.unwrap()
+	}
+
+	#[test]
+	fn validate_clean_file() {
+		let summary = validate("clean", &fixture::numbered(3));
+		assert_eq!(
+			summary,
+			ValidationSummary {
+				brick_count: 3,
+				warnings: Vec::new(),
+				is_valid: true,
+			}
+		);
+	}
+
+	#[test]
+	fn validate_bad_header() {
+		let save = fixture::numbered(2).replacen("Blockland", "Blocklad", 1);
+		let summary = validate("header", &save);
+		assert_eq!(summary.brick_count, 2);
+		assert_eq!(
+			summary.warnings,
+			vec![Warning::InvalidHeader(fixture::HEADER.replacen("Blockland", "Blocklad", 1))]
+		);
+		assert!(!summary.is_valid);
+	}
+
+	#[test]
+	fn validate_brick_fields() {
+		let save = fixture::save(&[
+			"1x1\" 0 0 0.2 4 0 0  0 0 1 1 1",
+			"1x1\" 0 0 0.2 0 0 64  0 0 1 1 1",
+			"1x1\" nan 0 0.2 0 0 0  0 0 1 1 1",
+			&fixture::line("1x1", (0.0, 0.0, 0.2)),
+		]);
+		let summary = validate("fields", &save);
+		assert_eq!(summary.brick_count, 4);
+		assert_eq!(
+			summary.warnings,
+			vec![
+				Warning::InvalidAngle { brick: 0, angle: 4 },
+				Warning::InvalidColorIndex {
+					brick: 1,
+					color_index: 64
+				},
+				Warning::NonFinitePosition { brick: 2 },
+			]
+		);
+		assert!(!summary.is_valid);
+	}
+
+	#[test]
+	fn validate_missing_file() {
+		let path = std::env::temp_dir().join("bl_save-missing.bls");
+		let error = validate_bls_file(&path).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::NotFound);
+	}
+}